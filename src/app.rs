@@ -1,4 +1,10 @@
-use dialoguer::Password;
+use crate::checkpoint::Checkpoint;
+use crate::hardware::{self, CpuVendor, GpuDevice};
+use crate::mirrors::{self, Mirror};
+use crate::network::{Interface, StaticAddress};
+use crate::pacman_conf::{self, CustomRepo};
+use crate::profiles::Profile;
+use crate::secret::Secret;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KernelSelection {
@@ -7,25 +13,99 @@ pub enum KernelSelection {
     Both,
 }
 
+impl KernelSelection {
+    pub const ALL: [KernelSelection; 3] = [KernelSelection::Linux, KernelSelection::LinuxLTS, KernelSelection::Both];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            KernelSelection::Linux => "linux",
+            KernelSelection::LinuxLTS => "linux-lts",
+            KernelSelection::Both => "both",
+        }
+    }
+
+    pub fn kernels(&self) -> &'static [&'static str] {
+        match self {
+            KernelSelection::Linux => &["linux"],
+            KernelSelection::LinuxLTS => &["linux-lts"],
+            KernelSelection::Both => &["linux", "linux-lts"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NvidiaDriver {
+    Proprietary,
+    Open,
+    Dkms,
+    Nouveau,
+}
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GpuDriver {
     Intel,
     AMD,
-    Nvidia,
+    Nvidia(NvidiaDriver),
     None,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DesktopEnv {
     None,
     KDE,
     GNOME,
     GnomeLockKDE,
+    Xfce,
+    Cinnamon,
+    MATE,
+    LXQt,
+    Budgie,
+    Sway,
+    Hyprland,
+    I3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightDmGreeter {
+    Gtk,
+    Slick,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayManager {
+    Sddm,
+    Gdm,
+    LightDm(LightDmGreeter),
+    Ly,
+    Greetd,
+    /// No display manager; the user logs in (or is auto-logged in) on tty1.
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkStack {
+    NetworkManager,
+    Networkd,
+    /// iwd handles Wi-Fi including DHCP, systemd-networkd the wired side.
+    Iwd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AurHelper {
+    Paru,
+    Yay,
+    None,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Page {
+    /// Offered instead of Welcome when an interrupted install left state behind.
+    Resume,
     Welcome,
+    Wifi,
+    Profile,
     GrubInstall,
     EfiPartition,
     RootPartition,
@@ -33,20 +113,34 @@ pub enum Page {
     Filesystem,
     Locale,
     Hostname,
+    Network,
     Username,
     UserPassword,
     RootPassword,
     Kernel,
     GpuDriver,
     DesktopEnv,
+    DesktopExtras,
+    DisplayManager,
+    Autologin,
+    PacmanConf,
+    ExtraPackages,
+    Aur,
+    Mirrors,
+    OfflineRepo,
+    Preflight,
     Summary,
+    Installing,
     Finished,
 }
 
 pub struct AppState {
     pub page: Page,
+    /// Set when a page was opened from the summary; leaving it goes back there.
+    pub return_to_summary: bool,
 
     // Installer options:
+    pub profile: Option<String>,
     pub install_grub: Option<bool>,
     pub efi_partition: Option<String>,
     pub root_partition: Option<String>,
@@ -55,17 +149,58 @@ pub struct AppState {
     pub locale: Option<String>,
     pub hostname: Option<String>,
     pub username: Option<String>,
-    pub user_password: Option<String>,
-    pub root_password: Option<String>,
+    pub user_password: Option<Secret>,
+    pub root_password: Option<Secret>,
     pub kernel: Option<KernelSelection>,
     pub gpu_driver: Option<GpuDriver>,
     pub desktop_env: Option<DesktopEnv>,
+    pub desktop_full: Option<bool>,
+    pub display_manager: Option<DisplayManager>,
+    pub autologin: Option<bool>,
+    pub multilib: Option<bool>,
+    pub parallel_downloads: Option<u32>,
+    pub pacman_color: Option<bool>,
+    pub i_love_candy: Option<bool>,
+    pub custom_repos: Vec<CustomRepo>,
+    pub network_stack: Option<NetworkStack>,
+    pub static_addresses: Vec<StaticAddress>,
+    pub copy_wifi: Option<bool>,
+    pub aur_helper: Option<AurHelper>,
+    pub aur_packages: Vec<String>,
+    pub extra_packages: Vec<String>,
+    pub extra_services: Vec<String>,
+    pub user_groups: Vec<String>,
+    pub mirror_country: Option<String>,
+    pub mirror_protocol: Option<String>,
+    /// Ordered server URLs to write; empty keeps the live mirrorlist.
+    pub mirrorlist: Vec<String>,
+    /// Local repository directory or `file://` URL; `None` installs online.
+    pub offline_repo: Option<String>,
+
+    // Hardware probe results:
+    pub detected_gpus: Vec<GpuDevice>,
+    pub cpu_vendor: Option<CpuVendor>,
+    pub detected_interfaces: Vec<Interface>,
+    /// Result of the last connectivity check on the live system.
+    pub online: Option<bool>,
+
+    // Profiles offered on the profile page:
+    pub available_profiles: Vec<Profile>,
+    pub profile_errors: Vec<String>,
+
+    // Mirrors parsed from the live mirrorlist, with measured speeds once ranked:
+    pub available_mirrors: Vec<Mirror>,
+
+    /// State of an interrupted installation found at startup.
+    pub resume: Option<Checkpoint>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
             page: Page::Welcome,
+            return_to_summary: false,
+            profile: None,
             install_grub: None,
             efi_partition: None,
             root_partition: None,
@@ -79,61 +214,106 @@ impl Default for AppState {
             kernel: Some(KernelSelection::Both),
             gpu_driver: Some(GpuDriver::None),
             desktop_env: Some(DesktopEnv::None),
+            desktop_full: Some(false),
+            display_manager: Some(DisplayManager::None),
+            autologin: Some(false),
+            multilib: Some(false),
+            parallel_downloads: Some(pacman_conf::DEFAULT_PARALLEL_DOWNLOADS),
+            pacman_color: Some(true),
+            i_love_candy: Some(false),
+            custom_repos: Vec::new(),
+            network_stack: Some(NetworkStack::NetworkManager),
+            static_addresses: Vec::new(),
+            copy_wifi: Some(true),
+            aur_helper: Some(AurHelper::None),
+            aur_packages: Vec::new(),
+            extra_packages: Vec::new(),
+            extra_services: Vec::new(),
+            user_groups: Vec::new(),
+            mirror_country: None,
+            mirror_protocol: Some("https".to_string()),
+            mirrorlist: Vec::new(),
+            offline_repo: None,
+            detected_gpus: Vec::new(),
+            cpu_vendor: None,
+            detected_interfaces: Vec::new(),
+            online: None,
+            available_profiles: Vec::new(),
+            profile_errors: Vec::new(),
+            available_mirrors: Vec::new(),
+            resume: None,
         }
     }
 }
 
 impl AppState {
-    pub fn next_page(&mut self) {
-        use Page::*;
-        self.page = match self.page {
-            Welcome => GrubInstall,
-            GrubInstall => EfiPartition,
-            EfiPartition => RootPartition,
-            RootPartition => RootFormat,
-            RootFormat => Filesystem,
-            Filesystem => Locale,
-            Locale => Hostname,
-            Hostname => Username,
-            Username => UserPassword,
-            UserPassword => RootPassword,
-            RootPassword => Kernel,
-            Kernel => GpuDriver,
-            GpuDriver => DesktopEnv,
-            DesktopEnv => Summary,
-            Summary => Finished,
-            Finished => Finished,
+    pub fn has_wireless(&self) -> bool {
+        self.detected_interfaces.iter().any(|i| i.wireless)
+    }
+
+    /// Opens `page` to change an answer, coming back to the summary afterwards.
+    pub fn edit_from_summary(&mut self, page: Page) {
+        self.page = page;
+        self.return_to_summary = true;
+    }
+
+    /// Ends an edit started from the summary; true if it went back there.
+    pub fn back_to_summary(&mut self) -> bool {
+        if !self.return_to_summary {
+            return false;
         }
+        self.return_to_summary = false;
+        self.page = Page::Summary;
+        true
     }
 
-    pub fn prev_page(&mut self) {
-        use Page::*;
-        self.page = match self.page {
-            Welcome => Welcome,
-            GrubInstall => Welcome,
-            EfiPartition => GrubInstall,
-            RootPartition => EfiPartition,
-            RootFormat => RootPartition,
-            Filesystem => RootFormat,
-            Locale => Filesystem,
-            Hostname => Locale,
-            Username => Hostname,
-            UserPassword => Username,
-            RootPassword => UserPassword,
-            Kernel => RootPassword,
-            GpuDriver => Kernel,
-            DesktopEnv => GpuDriver,
-            Summary => DesktopEnv,
-            Finished => Summary,
+    /// Selects a desktop. Choosing a different one (or the first one) resets
+    /// the display manager to its default; choosing the same one keeps it.
+    pub fn set_desktop_env(&mut self, desktop: DesktopEnv) {
+        if self.desktop_env != Some(desktop) || self.display_manager.is_none() {
+            self.display_manager = Some(desktop.def().display_manager);
         }
+        self.desktop_env = Some(desktop);
     }
 
-    pub fn prompt_password(&mut self, prompt: &str) {
-        let pw = Password::new().with_prompt(prompt).interact().unwrap_or_default();
-        if prompt.to_lowercase().contains("root") {
-            self.root_password = Some(pw);
+    /// Adds `name` to the extra packages, or removes it if already there.
+    pub fn toggle_extra_package(&mut self, name: &str) {
+        if let Some(pos) = self.extra_packages.iter().position(|p| p == name) {
+            self.extra_packages.remove(pos);
         } else {
-            self.user_password = Some(pw);
+            self.extra_packages.push(name.to_string());
+        }
+    }
+
+    /// Mirrors matching the current country and protocol filters.
+    pub fn filtered_mirrors(&self) -> Vec<&Mirror> {
+        mirrors::filter(&self.available_mirrors, self.mirror_country.as_deref(), self.mirror_protocol.as_deref())
+    }
+
+    /// Stores the detected GPUs and preselects the matching driver.
+    pub fn apply_gpu_probe(&mut self, gpus: Vec<GpuDevice>) {
+        if let Some(driver) = hardware::suggested_driver(&gpus) {
+            self.gpu_driver = Some(driver);
         }
+        self.detected_gpus = gpus;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reselecting_the_desktop_keeps_the_display_manager() {
+        let mut app = AppState::default();
+        app.set_desktop_env(DesktopEnv::KDE);
+        assert_eq!(app.display_manager, Some(DesktopEnv::KDE.def().display_manager));
+
+        app.display_manager = Some(DisplayManager::Ly);
+        app.set_desktop_env(DesktopEnv::KDE);
+        assert_eq!(app.display_manager, Some(DisplayManager::Ly));
+
+        app.set_desktop_env(DesktopEnv::GNOME);
+        assert_eq!(app.display_manager, Some(DesktopEnv::GNOME.def().display_manager));
+    }
+}
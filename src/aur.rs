@@ -0,0 +1,125 @@
+use crate::app::AurHelper;
+
+/// Lets the created user run pacman through sudo without a password while
+/// makepkg installs; only exists while an AUR step runs.
+pub const SUDOERS_PATH: &str = "/etc/sudoers.d/10-installarch-aur";
+const BUILD_DIR: &str = "/tmp/aur";
+const AUR_URL: &str = "https://aur.archlinux.org";
+
+/// Needed in the target before any AUR package can be built.
+pub const BUILD_DEPENDS: [&str; 2] = ["base-devel", "git"];
+
+impl AurHelper {
+    pub const ALL: [AurHelper; 3] = [AurHelper::Paru, AurHelper::Yay, AurHelper::None];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AurHelper::Paru => "paru",
+            AurHelper::Yay => "yay",
+            AurHelper::None => "None",
+        }
+    }
+
+    /// The prebuilt AUR package, so bootstrapping does not need a Rust or Go toolchain.
+    fn package(&self) -> Option<&'static str> {
+        match self {
+            AurHelper::Paru => Some("paru-bin"),
+            AurHelper::Yay => Some("yay-bin"),
+            AurHelper::None => None,
+        }
+    }
+
+    /// Shell script run as the build user that clones and installs the helper.
+    pub fn bootstrap_script(&self) -> Option<String> {
+        let package = self.package()?;
+        Some(format!(
+            "set -e; mkdir -p {dir}; rm -rf {dir}/{pkg}; git clone {url}/{pkg}.git {dir}/{pkg}; cd {dir}/{pkg}; makepkg -si --noconfirm",
+            dir = BUILD_DIR,
+            pkg = package,
+            url = AUR_URL,
+        ))
+    }
+
+    /// Command installing `packages` non-interactively with the helper.
+    pub fn install_command(&self, packages: &[String]) -> Option<Vec<String>> {
+        let mut args: Vec<String> = match self {
+            AurHelper::Paru => vec!["paru", "-S", "--noconfirm", "--needed", "--skipreview"],
+            AurHelper::Yay => vec!["yay", "-S", "--noconfirm", "--needed", "--answerdiff", "None", "--answerclean", "None"],
+            AurHelper::None => return None,
+        }
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        args.extend(packages.iter().cloned());
+        Some(args)
+    }
+}
+
+fn sudoers_rule(user: &str) -> String {
+    format!("{} ALL=(ALL:ALL) NOPASSWD: ALL", user)
+}
+
+/// Writes the rule, removes it on any exit and runs the command as the user.
+/// Arguments: rule path, rule, user, then the command.
+const WITH_RULE: &str = r#"rule=$1
+trap 'rm -f "$rule"' EXIT
+trap 'exit 1' INT TERM HUP
+printf '%s\n' "$2" > "$rule"
+chmod 0440 "$rule"
+user=$3
+shift 3
+sudo -H -u "$user" "$@""#;
+
+/// Runs `command` as `user` with the temporary sudo rule in place. The rule is
+/// written and removed by the same shell, so a failed, aborted or interrupted
+/// step never leaves it on the installed system.
+pub fn as_user_with_sudo(user: &str, command: &[&str]) -> Vec<String> {
+    let mut args: Vec<String> =
+        ["bash", "-c", WITH_RULE, "bash", SUDOERS_PATH, &sudoers_rule(user), user].iter().map(|a| a.to_string()).collect();
+    args.extend(command.iter().map(|a| a.to_string()));
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::process::Command;
+
+    /// Runs the wrapper with `sudo` replaced by a script that checks the rule
+    /// exists and then runs the command.
+    fn run_wrapper(name: &str, command: &[&str]) -> (bool, bool) {
+        let dir = std::env::temp_dir().join(format!("installarch-aur-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let rule = dir.join("rule");
+        let sudo = dir.join("sudo");
+        fs::write(&sudo, format!("#!/bin/sh\ntest -e '{}' || exit 99\nshift 3\nexec \"$@\"\n", rule.display())).unwrap();
+        fs::set_permissions(&sudo, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut args = as_user_with_sudo("bob", command);
+        args[4] = rule.display().to_string();
+        let path = format!("{}:{}", dir.display(), std::env::var("PATH").unwrap_or_default());
+        let status = Command::new(&args[0]).args(&args[1..]).env("PATH", path).status().unwrap();
+        let left_behind = rule.exists();
+        fs::remove_dir_all(&dir).unwrap();
+        (status.success(), left_behind)
+    }
+
+    #[test]
+    fn sudo_rule_is_removed_after_success() {
+        assert_eq!(run_wrapper("ok", &["true"]), (true, false));
+    }
+
+    #[test]
+    fn sudo_rule_is_removed_after_failure() {
+        assert_eq!(run_wrapper("fail", &["bash", "-c", "exit 3"]), (false, false));
+    }
+
+    #[test]
+    fn rule_names_the_user() {
+        let args = as_user_with_sudo("bob", &["true"]);
+        assert_eq!(args[4], SUDOERS_PATH);
+        assert_eq!(args[5], "bob ALL=(ALL:ALL) NOPASSWD: ALL");
+    }
+}
@@ -0,0 +1,60 @@
+// Resume state for an interrupted installation.
+//
+// The answers are stored in the usual config format plus the ids of the
+// steps that completed, so a later launch can rebuild the same plan and
+// continue from the first unfinished step.
+
+use crate::config::{self, Config};
+use std::fs;
+use std::path::Path;
+
+/// On the live system, so it survives restarting the installer but not a reboot.
+pub const STATE_PATH: &str = "/var/lib/installarch/state.conf";
+const COMPLETED_KEY: &str = "completed_steps";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub config: Config,
+    pub completed: Vec<String>,
+}
+
+impl Checkpoint {
+    pub fn new(config: Config) -> Self {
+        Checkpoint { config, completed: Vec::new() }
+    }
+
+    /// The saved state, if an earlier run left one behind.
+    pub fn load() -> Option<Result<Self, String>> {
+        let path = Path::new(STATE_PATH);
+        if !path.exists() {
+            return None;
+        }
+        Some(config::load(path).map(|config| Checkpoint { completed: config.get_list(COMPLETED_KEY), config }))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Path::new(STATE_PATH);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let mut config = self.config.clone();
+        config.set(COMPLETED_KEY, self.completed.join(" "));
+        config::save(path, &config)
+    }
+
+    pub fn mark_done(&mut self, id: &str) -> Result<(), String> {
+        if !self.is_done(id) {
+            self.completed.push(id.to_string());
+        }
+        self.save()
+    }
+
+    pub fn is_done(&self, id: &str) -> bool {
+        self.completed.iter().any(|c| c == id)
+    }
+}
+
+/// Removes the state once the installation has finished.
+pub fn clear() {
+    let _ = fs::remove_file(STATE_PATH);
+}
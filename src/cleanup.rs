@@ -0,0 +1,143 @@
+use std::process::Command;
+
+/// Something the execution phase set up on the live system that has to be
+/// released again if the installation stops early. The installer neither
+/// opens LUKS mappings nor activates volume groups, so only its own mounts
+/// are tracked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resource {
+    Mount(String),
+}
+
+impl Resource {
+    fn release_command(&self) -> Vec<&str> {
+        match self {
+            Resource::Mount(target) => vec!["umount", target],
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Resource::Mount(target) => format!("mount {}", target),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub released: Vec<String>,
+    /// What is still left behind, with the error for each.
+    pub failed: Vec<String>,
+}
+
+/// Resources the installer created, in the order it created them.
+#[derive(Debug, Default)]
+pub struct Tracker {
+    resources: Vec<Resource>,
+}
+
+impl Tracker {
+    pub fn track(&mut self, resource: Resource) {
+        if !self.resources.contains(&resource) {
+            self.resources.push(resource);
+        }
+    }
+
+    /// Forgets mounts that a successful live-system command released.
+    pub fn observe(&mut self, args: &[String]) {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match args.as_slice() {
+            ["umount", "-R", target] => {
+                let prefix = format!("{}/", target.trim_end_matches('/'));
+                self.resources.retain(|r| !matches!(r, Resource::Mount(m) if m == target || m.starts_with(&prefix)));
+            }
+            ["umount", target] => self.forget(&Resource::Mount(target.to_string())),
+            _ => {}
+        }
+    }
+
+    fn forget(&mut self, resource: &Resource) {
+        self.resources.retain(|r| r != resource);
+    }
+
+    /// Releases everything in reverse order of creation, so nested mounts go
+    /// before their parents.
+    pub fn release_all(&mut self) -> Report {
+        self.release_with(|args| {
+            let output = Command::new(args[0]).args(&args[1..]).output().map_err(|e| format!("{}: {}", args[0], e))?;
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(format!("`{}` failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+            }
+        })
+    }
+
+    fn release_with(&mut self, mut run: impl FnMut(&[&str]) -> Result<(), String>) -> Report {
+        let mut report = Report::default();
+        while let Some(resource) = self.resources.pop() {
+            match run(&resource.release_command()) {
+                Ok(()) => report.released.push(resource.describe()),
+                Err(err) => report.failed.push(format!("{}: {}", resource.describe(), err)),
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mounted(targets: &[&str]) -> Tracker {
+        let mut tracker = Tracker::default();
+        for target in targets {
+            tracker.track(Resource::Mount(target.to_string()));
+        }
+        tracker
+    }
+
+    fn args(command: &[&str]) -> Vec<String> {
+        command.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn recursive_umount_forgets_nested_mounts() {
+        let mut tracker = mounted(&["/mnt", "/mnt/boot", "/mnt2"]);
+        tracker.observe(&args(&["umount", "-R", "/mnt"]));
+        assert_eq!(tracker.resources, vec![Resource::Mount("/mnt2".into())]);
+    }
+
+    #[test]
+    fn umount_forgets_only_its_target() {
+        let mut tracker = mounted(&["/mnt", "/mnt/boot"]);
+        tracker.observe(&args(&["umount", "/mnt/boot"]));
+        tracker.observe(&args(&["cryptsetup", "open", "/dev/sda2", "root"]));
+        tracker.observe(&args(&["vgchange", "-ay", "vg0"]));
+        assert_eq!(tracker.resources, vec![Resource::Mount("/mnt".into())]);
+    }
+
+    #[test]
+    fn tracks_each_mount_once() {
+        let tracker = mounted(&["/mnt", "/mnt"]);
+        assert_eq!(tracker.resources.len(), 1);
+    }
+
+    #[test]
+    fn releases_in_reverse_order_and_reports_failures() {
+        let mut tracker = mounted(&["/mnt", "/mnt/boot", "/mnt/home"]);
+        let mut ran = Vec::new();
+        let report = tracker.release_with(|args| {
+            ran.push(args.join(" "));
+            if args[1] == "/mnt/boot" {
+                Err("target is busy".into())
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(ran, vec!["umount /mnt/home", "umount /mnt/boot", "umount /mnt"]);
+        assert_eq!(report.released, vec!["mount /mnt/home", "mount /mnt"]);
+        assert_eq!(report.failed, vec!["mount /mnt/boot: target is busy"]);
+        assert!(tracker.resources.is_empty());
+    }
+}
@@ -1 +1,419 @@
-// Will be used to store user choices or load/save config file later.
+// Plain `key = value` files used for profiles and saved answers.
+//
+// Lines starting with `#` are comments, list values are separated by
+// whitespace and booleans are written as `yes`/`no`.
+
+use crate::app::{AppState, AurHelper, DesktopEnv, DisplayManager, GpuDriver, KernelSelection, LightDmGreeter, NetworkStack, NvidiaDriver};
+use crate::network::StaticAddress;
+use crate::pacman_conf::{self, CustomRepo};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Where the Summary page saves the current answers.
+pub const DEFAULT_PATH: &str = "installarch.conf";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    entries: Vec<(String, String)>,
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = Config::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", i + 1));
+            };
+            config.set(key.trim(), value.trim());
+        }
+        Ok(config)
+    }
+
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        self.get(key)
+            .map(|v| v.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, String> {
+        self.get(key).map(|v| parse_bool(key, v)).transpose()
+    }
+
+    /// Reads an enum value through `from_key`, reporting unknown names.
+    pub fn get_parsed<T>(&self, key: &str, from_key: fn(&str) -> Option<T>) -> Result<Option<T>, String> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(v) => from_key(v).map(Some).ok_or_else(|| format!("{}: unknown value `{}`", key, v)),
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.entries {
+            writeln!(f, "{} = {}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn load(path: &Path) -> Result<Config, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Config::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn save(path: &Path, config: &Config) -> Result<(), String> {
+    fs::write(path, config.to_string()).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn bool_key(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(format!("{}: expected yes or no, got `{}`", key, value)),
+    }
+}
+
+impl KernelSelection {
+    pub fn key(&self) -> &'static str {
+        match self {
+            KernelSelection::Linux => "linux",
+            KernelSelection::LinuxLTS => "linux-lts",
+            KernelSelection::Both => "both",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        [KernelSelection::Linux, KernelSelection::LinuxLTS, KernelSelection::Both]
+            .into_iter()
+            .find(|k| k.key() == key)
+    }
+}
+
+impl GpuDriver {
+    pub fn key(&self) -> &'static str {
+        match self {
+            GpuDriver::Intel => "intel",
+            GpuDriver::AMD => "amd",
+            GpuDriver::Nvidia(NvidiaDriver::Proprietary) => "nvidia",
+            GpuDriver::Nvidia(NvidiaDriver::Open) => "nvidia-open",
+            GpuDriver::Nvidia(NvidiaDriver::Dkms) => "nvidia-dkms",
+            GpuDriver::Nvidia(NvidiaDriver::Nouveau) => "nouveau",
+            GpuDriver::None => "none",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        [
+            GpuDriver::Intel,
+            GpuDriver::AMD,
+            GpuDriver::Nvidia(NvidiaDriver::Proprietary),
+            GpuDriver::Nvidia(NvidiaDriver::Open),
+            GpuDriver::Nvidia(NvidiaDriver::Dkms),
+            GpuDriver::Nvidia(NvidiaDriver::Nouveau),
+            GpuDriver::None,
+        ]
+        .into_iter()
+        .find(|g| g.key() == key)
+    }
+}
+
+impl DesktopEnv {
+    pub fn key(&self) -> &'static str {
+        match self {
+            DesktopEnv::None => "none",
+            DesktopEnv::KDE => "kde",
+            DesktopEnv::GNOME => "gnome",
+            DesktopEnv::GnomeLockKDE => "gnome-lock-kde",
+            DesktopEnv::Xfce => "xfce",
+            DesktopEnv::Cinnamon => "cinnamon",
+            DesktopEnv::MATE => "mate",
+            DesktopEnv::LXQt => "lxqt",
+            DesktopEnv::Budgie => "budgie",
+            DesktopEnv::Sway => "sway",
+            DesktopEnv::Hyprland => "hyprland",
+            DesktopEnv::I3 => "i3",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        DesktopEnv::ALL.into_iter().find(|d| d.key() == key)
+    }
+}
+
+impl DisplayManager {
+    pub fn key(&self) -> &'static str {
+        match self {
+            DisplayManager::Sddm => "sddm",
+            DisplayManager::Gdm => "gdm",
+            DisplayManager::LightDm(LightDmGreeter::Gtk) => "lightdm-gtk",
+            DisplayManager::LightDm(LightDmGreeter::Slick) => "lightdm-slick",
+            DisplayManager::Ly => "ly",
+            DisplayManager::Greetd => "greetd",
+            DisplayManager::None => "none",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        DisplayManager::ALL.into_iter().find(|d| d.key() == key)
+    }
+}
+
+impl NetworkStack {
+    pub fn key(&self) -> &'static str {
+        match self {
+            NetworkStack::NetworkManager => "networkmanager",
+            NetworkStack::Networkd => "networkd",
+            NetworkStack::Iwd => "iwd",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        NetworkStack::ALL.into_iter().find(|n| n.key() == key)
+    }
+}
+
+impl AurHelper {
+    pub fn key(&self) -> &'static str {
+        match self {
+            AurHelper::Paru => "paru",
+            AurHelper::Yay => "yay",
+            AurHelper::None => "none",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        AurHelper::ALL.into_iter().find(|h| h.key() == key)
+    }
+}
+
+impl AppState {
+    /// The answers as a config file. Passwords are never written.
+    pub fn to_config(&self) -> Config {
+        let mut config = Config::default();
+        let mut set_opt = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                config.set(key, value);
+            }
+        };
+        set_opt("profile", self.profile.clone());
+        set_opt("install_grub", self.install_grub.map(|v| bool_key(v).to_string()));
+        set_opt("efi_partition", self.efi_partition.clone());
+        set_opt("root_partition", self.root_partition.clone());
+        set_opt("format_root", self.format_root.map(|v| bool_key(v).to_string()));
+        set_opt("filesystem", self.filesystem.clone());
+        set_opt("locale", self.locale.clone());
+        set_opt("hostname", self.hostname.clone());
+        set_opt("username", self.username.clone());
+        set_opt("network", self.network_stack.map(|n| n.key().to_string()));
+        set_opt("copy_wifi", self.copy_wifi.map(|v| bool_key(v).to_string()));
+        set_opt("kernel", self.kernel.map(|k| k.key().to_string()));
+        set_opt("gpu", self.gpu_driver.map(|g| g.key().to_string()));
+        set_opt("desktop", self.desktop_env.map(|d| d.key().to_string()));
+        set_opt("desktop_full", self.desktop_full.map(|v| bool_key(v).to_string()));
+        set_opt("display_manager", self.display_manager.map(|d| d.key().to_string()));
+        set_opt("autologin", self.autologin.map(|v| bool_key(v).to_string()));
+        set_opt("multilib", self.multilib.map(|v| bool_key(v).to_string()));
+        set_opt("parallel_downloads", self.parallel_downloads.map(|n| n.to_string()));
+        set_opt("pacman_color", self.pacman_color.map(|v| bool_key(v).to_string()));
+        set_opt("i_love_candy", self.i_love_candy.map(|v| bool_key(v).to_string()));
+        set_opt("aur_helper", self.aur_helper.map(|h| h.key().to_string()));
+        set_opt("mirror_country", self.mirror_country.clone());
+        set_opt("mirror_protocol", self.mirror_protocol.clone());
+        set_opt("offline_repo", self.offline_repo.clone());
+        config.set("static_addresses", self.static_addresses.iter().map(StaticAddress::key).collect::<Vec<_>>().join(" "));
+        config.set("packages", self.extra_packages.join(" "));
+        config.set("services", self.extra_services.join(" "));
+        config.set("groups", self.user_groups.join(" "));
+        config.set("aur_packages", self.aur_packages.join(" "));
+        config.set("mirrors", self.mirrorlist.join(" "));
+        config.set("repos", self.custom_repos.iter().map(CustomRepo::key).collect::<Vec<_>>().join(" "));
+        config
+    }
+
+    /// Overwrites the answers present in `config`; absent keys are left alone.
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), String> {
+        let string = |key: &str| config.get(key).map(str::to_string);
+        if let Some(v) = string("profile") {
+            self.profile = Some(v);
+        }
+        if let Some(v) = config.get_bool("install_grub")? {
+            self.install_grub = Some(v);
+        }
+        if let Some(v) = string("efi_partition") {
+            self.efi_partition = Some(v);
+        }
+        if let Some(v) = string("root_partition") {
+            self.root_partition = Some(v);
+        }
+        if let Some(v) = config.get_bool("format_root")? {
+            self.format_root = Some(v);
+        }
+        if let Some(v) = string("filesystem") {
+            self.filesystem = Some(v);
+        }
+        if let Some(v) = string("locale") {
+            self.locale = Some(v);
+        }
+        if let Some(v) = string("hostname") {
+            self.hostname = Some(v);
+        }
+        if let Some(v) = string("username") {
+            self.username = Some(v);
+        }
+        if let Some(v) = config.get_parsed("network", NetworkStack::from_key)? {
+            self.network_stack = Some(v);
+        }
+        if let Some(v) = config.get_bool("copy_wifi")? {
+            self.copy_wifi = Some(v);
+        }
+        if let Some(v) = config.get_parsed("kernel", KernelSelection::from_key)? {
+            self.kernel = Some(v);
+        }
+        if let Some(v) = config.get_parsed("gpu", GpuDriver::from_key)? {
+            self.gpu_driver = Some(v);
+        }
+        if let Some(v) = config.get_parsed("desktop", DesktopEnv::from_key)? {
+            self.set_desktop_env(v);
+        }
+        if let Some(v) = config.get_bool("desktop_full")? {
+            self.desktop_full = Some(v);
+        }
+        if let Some(v) = config.get_parsed("display_manager", DisplayManager::from_key)? {
+            self.display_manager = Some(v);
+        }
+        if let Some(v) = config.get_bool("autologin")? {
+            self.autologin = Some(v);
+        }
+        if let Some(v) = config.get_bool("multilib")? {
+            self.multilib = Some(v);
+        }
+        if let Some(v) = string("parallel_downloads") {
+            let n: u32 = v.parse().map_err(|_| format!("parallel_downloads: `{}` is not a number", v))?;
+            if !pacman_conf::PARALLEL_DOWNLOADS.contains(&n) {
+                return Err(format!(
+                    "parallel_downloads: {} is outside {}-{}",
+                    n,
+                    pacman_conf::PARALLEL_DOWNLOADS.start(),
+                    pacman_conf::PARALLEL_DOWNLOADS.end()
+                ));
+            }
+            self.parallel_downloads = Some(n);
+        }
+        if let Some(v) = config.get_bool("pacman_color")? {
+            self.pacman_color = Some(v);
+        }
+        if let Some(v) = config.get_bool("i_love_candy")? {
+            self.i_love_candy = Some(v);
+        }
+        if let Some(v) = config.get_parsed("aur_helper", AurHelper::from_key)? {
+            self.aur_helper = Some(v);
+        }
+        if let Some(v) = string("mirror_country") {
+            self.mirror_country = Some(v);
+        }
+        if let Some(v) = string("mirror_protocol") {
+            self.mirror_protocol = Some(v);
+        }
+        if let Some(v) = string("offline_repo") {
+            self.offline_repo = Some(v);
+        }
+        if config.get("static_addresses").is_some() {
+            self.static_addresses = config
+                .get_list("static_addresses")
+                .iter()
+                .map(|a| StaticAddress::from_key(a).ok_or_else(|| format!("static_addresses: invalid entry `{}`", a)))
+                .collect::<Result<_, _>>()?;
+        }
+        if config.get("packages").is_some() {
+            self.extra_packages = config.get_list("packages");
+        }
+        if config.get("services").is_some() {
+            self.extra_services = config.get_list("services");
+        }
+        if config.get("groups").is_some() {
+            self.user_groups = config.get_list("groups");
+        }
+        if config.get("aur_packages").is_some() {
+            self.aur_packages = config.get_list("aur_packages");
+        }
+        if config.get("mirrors").is_some() {
+            self.mirrorlist = config.get_list("mirrors");
+        }
+        if config.get("repos").is_some() {
+            self.custom_repos = config
+                .get_list("repos")
+                .iter()
+                .map(|r| CustomRepo::from_key(r).ok_or_else(|| format!("repos: invalid entry `{}`", r)))
+                .collect::<Result<_, _>>()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys_values_and_comments() {
+        let config = Config::parse("# answers\n\nhostname = arch\nextra_packages = vim  git\nmultilib=yes\nhostname = box\n").unwrap();
+        assert_eq!(config.get("hostname"), Some("box"));
+        assert_eq!(config.get_list("extra_packages"), vec!["vim", "git"]);
+        assert_eq!(config.get_bool("multilib"), Ok(Some(true)));
+        assert_eq!(config.get_bool("missing"), Ok(None));
+        assert_eq!(config.get("missing"), None);
+    }
+
+    #[test]
+    fn reports_malformed_lines_and_values() {
+        assert_eq!(Config::parse("hostname arch\n"), Err("line 1: expected `key = value`".to_string()));
+        assert!(Config::parse("multilib = maybe\n").unwrap().get_bool("multilib").is_err());
+        let config = Config::parse("kernel = hurd\n").unwrap();
+        assert!(config.get_parsed("kernel", |_| None::<()>).is_err());
+    }
+
+    #[test]
+    fn rejects_parallel_downloads_out_of_range() {
+        let mut app = AppState::default();
+        assert!(app.apply_config(&Config::parse("parallel_downloads = 0\n").unwrap()).is_err());
+        assert!(app.apply_config(&Config::parse("parallel_downloads = 21\n").unwrap()).is_err());
+        assert_eq!(app.apply_config(&Config::parse("parallel_downloads = 8\n").unwrap()), Ok(()));
+        assert_eq!(app.parallel_downloads, Some(8));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let config = Config::parse("hostname = arch\nusers = a b\n").unwrap();
+        assert_eq!(Config::parse(&config.to_string()), Ok(config));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::app::{DesktopEnv, DisplayManager, LightDmGreeter};
+
+/// Everything the installer needs to know to set up one desktop choice.
+pub struct DesktopDef {
+    pub label: &'static str,
+    pub description: &'static str,
+    /// Installed for the minimal variant.
+    pub packages: &'static [&'static str],
+    /// Added on top of `packages` for the full variant.
+    pub extras: &'static [&'static str],
+    /// Session file name under /usr/share/{wayland-,x}sessions, for autologin.
+    pub session: Option<&'static str>,
+    /// Command that starts the session from a console greeter.
+    pub exec: Option<&'static str>,
+    /// Display manager preselected for this desktop.
+    pub display_manager: DisplayManager,
+    pub services: &'static [&'static str],
+}
+
+const NONE: DesktopDef = DesktopDef {
+    label: "None",
+    description: "Console only, no graphical environment",
+    packages: &[],
+    extras: &[],
+    session: None,
+    exec: None,
+    display_manager: DisplayManager::None,
+    services: &[],
+};
+
+const KDE: DesktopDef = DesktopDef {
+    label: "KDE Plasma",
+    description: "Feature-rich, highly configurable Qt desktop",
+    packages: &["plasma", "konsole", "dolphin", "bluez", "bluez-utils"],
+    extras: &["kde-applications-meta"],
+    session: Some("plasma"),
+    exec: Some("startplasma-wayland"),
+    display_manager: DisplayManager::Sddm,
+    services: &["bluetooth"],
+};
+
+const GNOME: DesktopDef = DesktopDef {
+    label: "GNOME",
+    description: "Modern GTK desktop with an activities overview",
+    packages: &["gnome", "bluez", "bluez-utils"],
+    extras: &["gnome-extra", "gnome-tweaks"],
+    session: Some("gnome"),
+    exec: Some("gnome-session"),
+    display_manager: DisplayManager::Gdm,
+    services: &["bluetooth"],
+};
+
+const GNOME_LOCK_KDE: DesktopDef = DesktopDef {
+    label: "GNOME lock screen + KDE",
+    description: "KDE Plasma session behind the GNOME login screen (GDM)",
+    packages: &["plasma", "konsole", "dolphin", "bluez", "bluez-utils"],
+    extras: &["kde-applications-meta"],
+    session: Some("plasma"),
+    exec: Some("startplasma-wayland"),
+    display_manager: DisplayManager::Gdm,
+    services: &["bluetooth"],
+};
+
+const XFCE: DesktopDef = DesktopDef {
+    label: "Xfce",
+    description: "Lightweight, traditional GTK desktop",
+    packages: &["xorg-server", "xfce4"],
+    extras: &["xfce4-goodies", "network-manager-applet"],
+    session: Some("xfce"),
+    exec: Some("startxfce4"),
+    display_manager: DisplayManager::LightDm(LightDmGreeter::Gtk),
+    services: &[],
+};
+
+const CINNAMON: DesktopDef = DesktopDef {
+    label: "Cinnamon",
+    description: "Familiar Windows-like layout from Linux Mint",
+    packages: &["xorg-server", "cinnamon", "gnome-terminal", "bluez", "bluez-utils"],
+    extras: &["nemo-fileroller", "blueman", "xed"],
+    session: Some("cinnamon"),
+    exec: Some("cinnamon-session"),
+    display_manager: DisplayManager::LightDm(LightDmGreeter::Slick),
+    services: &["bluetooth"],
+};
+
+const MATE: DesktopDef = DesktopDef {
+    label: "MATE",
+    description: "Continuation of the classic GNOME 2 desktop",
+    packages: &["xorg-server", "mate"],
+    extras: &["mate-extra", "network-manager-applet"],
+    session: Some("mate"),
+    exec: Some("mate-session"),
+    display_manager: DisplayManager::LightDm(LightDmGreeter::Gtk),
+    services: &[],
+};
+
+const LXQT: DesktopDef = DesktopDef {
+    label: "LXQt",
+    description: "Lightweight Qt desktop for older hardware",
+    packages: &["xorg-server", "lxqt", "breeze-icons"],
+    extras: &["featherpad", "pavucontrol-qt", "nm-tray"],
+    session: Some("lxqt"),
+    exec: Some("startlxqt"),
+    display_manager: DisplayManager::Sddm,
+    services: &[],
+};
+
+const BUDGIE: DesktopDef = DesktopDef {
+    label: "Budgie",
+    description: "Simple, elegant GTK desktop from Solus",
+    packages: &["xorg-server", "budgie-desktop", "gnome-terminal", "nemo"],
+    extras: &["budgie-extras", "network-manager-applet"],
+    session: Some("budgie-desktop"),
+    exec: Some("budgie-desktop"),
+    display_manager: DisplayManager::LightDm(LightDmGreeter::Gtk),
+    services: &[],
+};
+
+const SWAY: DesktopDef = DesktopDef {
+    label: "Sway",
+    description: "Tiling Wayland compositor compatible with i3",
+    packages: &["sway", "swaylock", "swayidle", "swaybg", "foot", "wmenu"],
+    extras: &["waybar", "mako", "grim", "slurp", "xorg-xwayland"],
+    session: Some("sway"),
+    exec: Some("sway"),
+    display_manager: DisplayManager::Greetd,
+    services: &[],
+};
+
+const HYPRLAND: DesktopDef = DesktopDef {
+    label: "Hyprland",
+    description: "Dynamic tiling Wayland compositor with animations",
+    packages: &["hyprland", "kitty", "xdg-desktop-portal-hyprland", "polkit-kde-agent"],
+    extras: &["waybar", "wofi", "mako", "hyprpaper", "xorg-xwayland"],
+    session: Some("hyprland"),
+    exec: Some("Hyprland"),
+    display_manager: DisplayManager::Greetd,
+    services: &[],
+};
+
+const I3: DesktopDef = DesktopDef {
+    label: "i3",
+    description: "Keyboard-driven tiling window manager for X11",
+    packages: &["xorg-server", "xorg-xinit", "i3-wm", "i3status", "i3lock", "dmenu", "xterm"],
+    extras: &["picom", "feh", "rofi", "dunst"],
+    session: Some("i3"),
+    exec: Some("i3"),
+    display_manager: DisplayManager::LightDm(LightDmGreeter::Gtk),
+    services: &[],
+};
+
+impl DesktopEnv {
+    pub const ALL: [DesktopEnv; 12] = [
+        DesktopEnv::None,
+        DesktopEnv::KDE,
+        DesktopEnv::GNOME,
+        DesktopEnv::GnomeLockKDE,
+        DesktopEnv::Xfce,
+        DesktopEnv::Cinnamon,
+        DesktopEnv::MATE,
+        DesktopEnv::LXQt,
+        DesktopEnv::Budgie,
+        DesktopEnv::Sway,
+        DesktopEnv::Hyprland,
+        DesktopEnv::I3,
+    ];
+
+    pub fn def(&self) -> &'static DesktopDef {
+        match self {
+            DesktopEnv::None => &NONE,
+            DesktopEnv::KDE => &KDE,
+            DesktopEnv::GNOME => &GNOME,
+            DesktopEnv::GnomeLockKDE => &GNOME_LOCK_KDE,
+            DesktopEnv::Xfce => &XFCE,
+            DesktopEnv::Cinnamon => &CINNAMON,
+            DesktopEnv::MATE => &MATE,
+            DesktopEnv::LXQt => &LXQT,
+            DesktopEnv::Budgie => &BUDGIE,
+            DesktopEnv::Sway => &SWAY,
+            DesktopEnv::Hyprland => &HYPRLAND,
+            DesktopEnv::I3 => &I3,
+        }
+    }
+
+    /// Packages for this desktop, with the optional extras when `full` is set.
+    pub fn packages(&self, full: bool) -> Vec<&'static str> {
+        let def = self.def();
+        let mut pkgs = def.packages.to_vec();
+        if full {
+            pkgs.extend(def.extras);
+        }
+        pkgs
+    }
+}
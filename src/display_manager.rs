@@ -0,0 +1,130 @@
+use crate::app::{DisplayManager, LightDmGreeter};
+use crate::desktop::DesktopDef;
+
+const TTY_AUTOLOGIN_PATH: &str = "/etc/systemd/system/getty@tty1.service.d/autologin.conf";
+
+impl DisplayManager {
+    pub const ALL: [DisplayManager; 7] = [
+        DisplayManager::Sddm,
+        DisplayManager::Gdm,
+        DisplayManager::LightDm(LightDmGreeter::Gtk),
+        DisplayManager::LightDm(LightDmGreeter::Slick),
+        DisplayManager::Ly,
+        DisplayManager::Greetd,
+        DisplayManager::None,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplayManager::Sddm => "SDDM",
+            DisplayManager::Gdm => "GDM",
+            DisplayManager::LightDm(LightDmGreeter::Gtk) => "LightDM (GTK greeter)",
+            DisplayManager::LightDm(LightDmGreeter::Slick) => "LightDM (Slick greeter)",
+            DisplayManager::Ly => "Ly (console)",
+            DisplayManager::Greetd => "greetd + tuigreet",
+            DisplayManager::None => "None (log in on tty1)",
+        }
+    }
+
+    pub fn packages(&self) -> &'static [&'static str] {
+        match self {
+            DisplayManager::Sddm => &["sddm"],
+            DisplayManager::Gdm => &["gdm"],
+            DisplayManager::LightDm(LightDmGreeter::Gtk) => &["lightdm", "lightdm-gtk-greeter"],
+            DisplayManager::LightDm(LightDmGreeter::Slick) => &["lightdm", "lightdm-slick-greeter"],
+            DisplayManager::Ly => &["ly"],
+            DisplayManager::Greetd => &["greetd", "greetd-tuigreet"],
+            DisplayManager::None => &[],
+        }
+    }
+
+    pub fn service(&self) -> Option<&'static str> {
+        match self {
+            DisplayManager::Sddm => Some("sddm"),
+            DisplayManager::Gdm => Some("gdm"),
+            DisplayManager::LightDm(_) => Some("lightdm"),
+            DisplayManager::Ly => Some("ly"),
+            DisplayManager::Greetd => Some("greetd"),
+            DisplayManager::None => None,
+        }
+    }
+
+    /// Ly has no autologin support; graphical greeters need a session to start.
+    pub fn supports_autologin(&self, desktop: &DesktopDef) -> bool {
+        match self {
+            DisplayManager::Ly => false,
+            DisplayManager::None => true,
+            DisplayManager::Greetd => desktop.exec.is_some(),
+            _ => desktop.session.is_some(),
+        }
+    }
+
+    /// Groups the autologin user must belong to.
+    pub fn autologin_groups(&self) -> &'static [&'static str] {
+        match self {
+            DisplayManager::LightDm(_) => &["autologin"],
+            _ => &[],
+        }
+    }
+
+    /// Configuration files (path inside the target, contents) for the greeter
+    /// choice and, when `autologin_user` is set, automatic login.
+    pub fn config_files(&self, desktop: &DesktopDef, autologin_user: Option<&str>) -> Vec<(String, String)> {
+        let autologin_user = autologin_user.filter(|_| self.supports_autologin(desktop));
+        let session = desktop.session.unwrap_or_default();
+        let mut files = Vec::new();
+        match self {
+            DisplayManager::Sddm => {
+                if let Some(user) = autologin_user {
+                    files.push((
+                        "/etc/sddm.conf.d/autologin.conf".to_string(),
+                        format!("[Autologin]\nUser={}\nSession={}\n", user, session),
+                    ));
+                }
+            }
+            DisplayManager::Gdm => {
+                if let Some(user) = autologin_user {
+                    files.push((
+                        "/etc/gdm/custom.conf".to_string(),
+                        format!("[daemon]\nAutomaticLoginEnable=True\nAutomaticLogin={}\n", user),
+                    ));
+                }
+            }
+            DisplayManager::LightDm(greeter) => {
+                let greeter = match greeter {
+                    LightDmGreeter::Gtk => "lightdm-gtk-greeter",
+                    LightDmGreeter::Slick => "lightdm-slick-greeter",
+                };
+                let mut conf = format!("[Seat:*]\ngreeter-session={}\n", greeter);
+                if let Some(user) = autologin_user {
+                    conf.push_str(&format!("autologin-user={}\nautologin-session={}\n", user, session));
+                }
+                files.push(("/etc/lightdm/lightdm.conf.d/50-installer.conf".to_string(), conf));
+            }
+            DisplayManager::Ly => {}
+            DisplayManager::Greetd => {
+                let exec = desktop.exec.unwrap_or("$SHELL");
+                let mut conf = format!(
+                    "[terminal]\nvt = 1\n\n[default_session]\ncommand = \"tuigreet --time --remember --cmd {}\"\nuser = \"greeter\"\n",
+                    exec
+                );
+                if let Some(user) = autologin_user {
+                    conf.push_str(&format!("\n[initial_session]\ncommand = \"{}\"\nuser = \"{}\"\n", exec, user));
+                }
+                files.push(("/etc/greetd/config.toml".to_string(), conf));
+            }
+            DisplayManager::None => {
+                if let Some(user) = autologin_user {
+                    files.push((
+                        TTY_AUTOLOGIN_PATH.to_string(),
+                        format!(
+                            "[Service]\nExecStart=\nExecStart=-/sbin/agetty -o '-p -f -- \\\\u' --noclear --autologin {} %I $TERM\n",
+                            user
+                        ),
+                    ));
+                }
+            }
+        }
+        files
+    }
+}
@@ -0,0 +1,280 @@
+use crate::app::{GpuDriver, NvidiaDriver};
+use std::fs;
+use std::path::Path;
+
+const CPUINFO: &str = "/proc/cpuinfo";
+const EFI_FIRMWARE: &str = "/sys/firmware/efi";
+const PCI_DEVICES: &str = "/sys/bus/pci/devices";
+const PCI_IDS: [&str; 2] = ["/usr/share/hwdata/pci.ids", "/usr/share/misc/pci.ids"];
+
+/// PCI base class for display controllers (VGA, 3D, other display).
+const PCI_CLASS_DISPLAY: u32 = 0x03;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuVendor {
+    Intel,
+    Amd,
+}
+
+impl CpuVendor {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CpuVendor::Intel => "Intel",
+            CpuVendor::Amd => "AMD",
+        }
+    }
+
+    pub fn microcode_package(&self) -> &'static str {
+        match self {
+            CpuVendor::Intel => "intel-ucode",
+            CpuVendor::Amd => "amd-ucode",
+        }
+    }
+
+    /// The early microcode image the package places in `/boot`.
+    pub fn microcode_image(&self) -> &'static str {
+        match self {
+            CpuVendor::Intel => "/boot/intel-ucode.img",
+            CpuVendor::Amd => "/boot/amd-ucode.img",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuVendor {
+    Intel,
+    Amd,
+    Nvidia,
+    Other(u16),
+}
+
+impl GpuVendor {
+    fn from_id(id: u16) -> Self {
+        match id {
+            0x8086 => GpuVendor::Intel,
+            0x1002 => GpuVendor::Amd,
+            0x10de => GpuVendor::Nvidia,
+            other => GpuVendor::Other(other),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GpuVendor::Intel => "Intel",
+            GpuVendor::Amd => "AMD",
+            GpuVendor::Nvidia => "NVIDIA",
+            GpuVendor::Other(_) => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuDevice {
+    pub vendor: GpuVendor,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub name: String,
+}
+
+/// Reads the CPU vendor from the first `vendor_id` line of `/proc/cpuinfo`.
+pub fn probe_cpu_vendor() -> Option<CpuVendor> {
+    let cpuinfo = fs::read_to_string(CPUINFO).ok()?;
+    let vendor = cpuinfo
+        .lines()
+        .find(|l| l.starts_with("vendor_id"))?
+        .split(':')
+        .nth(1)?
+        .trim();
+    match vendor {
+        "GenuineIntel" => Some(CpuVendor::Intel),
+        "AuthenticAMD" => Some(CpuVendor::Amd),
+        _ => None,
+    }
+}
+
+/// Lists display controllers found under `/sys/bus/pci/devices`.
+pub fn probe_gpus() -> Vec<GpuDevice> {
+    probe_gpus_in(Path::new(PCI_DEVICES))
+}
+
+fn probe_gpus_in(root: &Path) -> Vec<GpuDevice> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+
+    let pci_ids = PCI_IDS.iter().find_map(|p| fs::read_to_string(p).ok());
+
+    let mut gpus = Vec::new();
+    for path in paths {
+        let Some(class) = read_hex(&path.join("class")) else {
+            continue;
+        };
+        if class >> 16 != PCI_CLASS_DISPLAY {
+            continue;
+        }
+        let (Some(vendor_id), Some(device_id)) = (read_hex(&path.join("vendor")), read_hex(&path.join("device"))) else {
+            continue;
+        };
+        let (vendor_id, device_id) = (vendor_id as u16, device_id as u16);
+        let vendor = GpuVendor::from_id(vendor_id);
+        let name = pci_ids
+            .as_deref()
+            .and_then(|ids| lookup_pci_name(ids, vendor_id, device_id))
+            .unwrap_or_else(|| format!("{} GPU [{:04x}:{:04x}]", vendor.label(), vendor_id, device_id));
+        gpus.push(GpuDevice { vendor, vendor_id, device_id, name });
+    }
+    gpus
+}
+
+fn read_hex(path: &Path) -> Option<u32> {
+    let raw = fs::read_to_string(path).ok()?;
+    let raw = raw.trim();
+    u32::from_str_radix(raw.trim_start_matches("0x"), 16).ok()
+}
+
+/// Resolves "Vendor Device" from the pci.ids database shipped by hwdata.
+fn lookup_pci_name(ids: &str, vendor_id: u16, device_id: u16) -> Option<String> {
+    let vendor_key = format!("{:04x}", vendor_id);
+    let device_key = format!("{:04x}", device_id);
+    let mut vendor_name: Option<&str> = None;
+
+    for line in ids.lines() {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        match vendor_name {
+            None => {
+                if let Some(rest) = line.strip_prefix(vendor_key.as_str()) {
+                    vendor_name = Some(rest.trim());
+                }
+            }
+            Some(vendor) => {
+                if !line.starts_with('\t') {
+                    return Some(format!("{} [{}:{}]", vendor, vendor_key, device_key));
+                }
+                if line.starts_with("\t\t") {
+                    continue;
+                }
+                if let Some(rest) = line[1..].strip_prefix(device_key.as_str()) {
+                    return Some(format!("{} {}", vendor, rest.trim()));
+                }
+            }
+        }
+    }
+    vendor_name.map(|vendor| format!("{} [{}:{}]", vendor, vendor_key, device_key))
+}
+
+impl GpuDriver {
+    pub const ALL: [GpuDriver; 7] = [
+        GpuDriver::Intel,
+        GpuDriver::AMD,
+        GpuDriver::Nvidia(NvidiaDriver::Proprietary),
+        GpuDriver::Nvidia(NvidiaDriver::Open),
+        GpuDriver::Nvidia(NvidiaDriver::Dkms),
+        GpuDriver::Nvidia(NvidiaDriver::Nouveau),
+        GpuDriver::None,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GpuDriver::Intel => "Intel",
+            GpuDriver::AMD => "AMD",
+            GpuDriver::Nvidia(nv) => nv.label(),
+            GpuDriver::None => "None",
+        }
+    }
+}
+
+/// Picks the driver for the most capable GPU present. On hybrid laptops the
+/// discrete card wins; the integrated one keeps working through mesa.
+pub fn suggested_driver(gpus: &[GpuDevice]) -> Option<GpuDriver> {
+    let has = |v: GpuVendor| gpus.iter().any(|g| g.vendor == v);
+    if has(GpuVendor::Nvidia) {
+        Some(GpuDriver::Nvidia(NvidiaDriver::Proprietary))
+    } else if has(GpuVendor::Amd) {
+        Some(GpuDriver::AMD)
+    } else if has(GpuVendor::Intel) {
+        Some(GpuDriver::Intel)
+    } else {
+        None
+    }
+}
+
+/// True when more than one GPU vendor is present (e.g. Intel iGPU + NVIDIA dGPU).
+pub fn is_hybrid(gpus: &[GpuDevice]) -> bool {
+    gpus.iter()
+        .skip(1)
+        .any(|g| g.vendor != gpus[0].vendor)
+}
+
+/// Whether the live system was booted in UEFI mode.
+pub fn is_uefi() -> bool {
+    Path::new(EFI_FIRMWARE).is_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDS: &str = "\
+# pci.ids excerpt
+10de  NVIDIA Corporation
+\t1c82  GP107 [GeForce GTX 1050 Ti]
+\t\t1043 8613  Subsystem name
+\t2484  GA104 [GeForce RTX 3070]
+8086  Intel Corporation
+\t9a49  TigerLake-LP GT2 [Iris Xe Graphics]
+";
+
+    #[test]
+    fn finds_device_names() {
+        assert_eq!(lookup_pci_name(IDS, 0x10de, 0x2484).as_deref(), Some("NVIDIA Corporation GA104 [GeForce RTX 3070]"));
+        assert_eq!(
+            lookup_pci_name(IDS, 0x8086, 0x9a49).as_deref(),
+            Some("Intel Corporation TigerLake-LP GT2 [Iris Xe Graphics]")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_vendor_and_ids() {
+        assert_eq!(lookup_pci_name(IDS, 0x10de, 0x1043).as_deref(), Some("NVIDIA Corporation [10de:1043]"));
+        assert_eq!(lookup_pci_name(IDS, 0x8086, 0x0001).as_deref(), Some("Intel Corporation [8086:0001]"));
+        assert_eq!(lookup_pci_name(IDS, 0x1002, 0x73bf), None);
+    }
+
+    #[test]
+    fn probes_display_controllers_from_sysfs() {
+        let root = std::env::temp_dir().join(format!("installarch-pci-{}", std::process::id()));
+        let device = |slot: &str, class: &str, vendor: &str, device: &str| {
+            let dir = root.join(slot);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("class"), format!("{}\n", class)).unwrap();
+            fs::write(dir.join("vendor"), format!("{}\n", vendor)).unwrap();
+            fs::write(dir.join("device"), format!("{}\n", device)).unwrap();
+        };
+        device("0000:00:02.0", "0x030000", "0x8086", "0x9a49");
+        device("0000:00:18.3", "0x060000", "0x1022", "0x1653");
+        device("0000:01:00.0", "0x030200", "0x10de", "0x2484");
+        device("0000:03:00.0", "0x038000", "0x1002", "0x73bf");
+        fs::create_dir_all(root.join("0000:04:00.0")).unwrap();
+
+        let gpus: Vec<(GpuVendor, u16, u16)> = probe_gpus_in(&root).iter().map(|g| (g.vendor, g.vendor_id, g.device_id)).collect();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            gpus,
+            vec![
+                (GpuVendor::Intel, 0x8086, 0x9a49),
+                (GpuVendor::Nvidia, 0x10de, 0x2484),
+                (GpuVendor::Amd, 0x1002, 0x73bf),
+            ]
+        );
+    }
+
+    #[test]
+    fn amd_chipset_id_is_not_a_gpu_vendor() {
+        assert_eq!(GpuVendor::from_id(0x1002), GpuVendor::Amd);
+        assert_eq!(GpuVendor::from_id(0x1022), GpuVendor::Other(0x1022));
+    }
+}
@@ -0,0 +1,680 @@
+use crate::app::{AppState, AurHelper, DesktopEnv, DisplayManager, GpuDriver, KernelSelection, NetworkStack};
+use crate::aur;
+use crate::cleanup::{Resource, Tracker};
+use crate::hardware;
+use crate::logging::{self, Kind};
+use crate::mirrors;
+use crate::network;
+use crate::nvidia;
+use crate::offline;
+use crate::packages;
+use crate::pacman_conf;
+use crate::secret::Secret;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+
+pub const TARGET: &str = "/mnt";
+
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Runs a command on the live system.
+    Run(Vec<String>),
+    /// Runs a command inside the target through `arch-chroot`.
+    Chroot(Vec<String>),
+    /// Like `Chroot`, with `input` piped to the command's stdin. The input
+    /// carries passwords, so it is never logged or printed.
+    ChrootInput(Vec<String>, Secret),
+    /// Writes a file on the live system, replacing any existing content.
+    WriteLiveFile { path: String, contents: String },
+    /// Writes a file inside the target, replacing any existing content.
+    WriteFile { path: String, contents: String },
+    /// Writes a file inside the target readable only by root.
+    WriteSecretFile { path: String, contents: Secret },
+    /// Mounts `source` on `target` unless something is already mounted there.
+    Mount { source: String, target: String },
+    /// Runs `command` in the target unless `check` succeeds there first.
+    ChrootUnless { check: Vec<String>, command: Vec<String> },
+}
+
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub name: String,
+    pub actions: Vec<Action>,
+    /// A failure is reported and the install carries on with the next step.
+    pub optional: bool,
+    /// Only prepares the live system (mounts, files under /tmp), so it runs
+    /// again when resuming even if it completed before.
+    pub rerun: bool,
+}
+
+impl Step {
+    pub fn id(&self) -> String {
+        step_id(&self.name)
+    }
+}
+
+/// Stable identifier recorded in the resume checkpoint.
+pub fn step_id(name: &str) -> String {
+    name.to_lowercase().replace(' ', "-")
+}
+
+/// The step that sets passwords; resuming before it needs them entered again.
+pub const CREATE_USER_STEP: &str = "Create user";
+
+#[derive(Debug)]
+pub enum Progress {
+    StepStarted(usize, String),
+    /// The step with this id completed (or, if optional, was given up on).
+    StepDone(String),
+    Output(String),
+    Failed(String),
+    /// Resources that could not be released after a failure.
+    CleanupFailed(Vec<String>),
+    /// Stopped on request before the step with this name.
+    Aborted(String),
+    Done,
+}
+
+fn cmd(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+fn step(name: &str, actions: Vec<Action>) -> Step {
+    Step { name: name.to_string(), actions, optional: false, rerun: false }
+}
+
+fn live_step(name: &str, actions: Vec<Action>) -> Step {
+    Step { rerun: true, ..step(name, actions) }
+}
+
+fn optional_step(name: &str, actions: Vec<Action>) -> Step {
+    Step { optional: true, ..step(name, actions) }
+}
+
+/// Turns `/dev/sda2` into `/dev/sda` and `/dev/nvme0n1p2` into `/dev/nvme0n1`.
+fn parent_disk(partition: &str) -> String {
+    let trimmed = partition.trim_end_matches(|c: char| c.is_ascii_digit());
+    let has_p_suffix = trimmed.ends_with('p')
+        && trimmed[..trimmed.len() - 1].ends_with(|c: char| c.is_ascii_digit());
+    if has_p_suffix {
+        trimmed[..trimmed.len() - 1].to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn kernel_params(app: &AppState) -> Vec<&'static str> {
+    let mut params = Vec::new();
+    if let Some(GpuDriver::Nvidia(driver)) = app.gpu_driver {
+        if driver.needs_kms_setup() {
+            params.extend(nvidia::KERNEL_PARAMS);
+        }
+    }
+    params
+}
+
+/// Builds the ordered list of install steps from the wizard answers.
+pub fn plan(app: &AppState) -> Vec<Step> {
+    let root = app.root_partition.clone().unwrap_or_default();
+    let filesystem = app.filesystem.as_deref().unwrap_or("ext4");
+    let locale = app.locale.as_deref().unwrap_or("en_US.UTF-8");
+    let hostname = app.hostname.as_deref().unwrap_or("archlinux");
+    let username = app.username.as_deref().unwrap_or("user");
+    let kernel = app.kernel.unwrap_or(KernelSelection::Both);
+    let uefi = hardware::is_uefi();
+
+    let packages = packages::resolve(app);
+    let mut steps = Vec::new();
+
+    // Offline installs resolve every package against the local repository
+    // before anything on disk is touched.
+    if let Some(repo) = &app.offline_repo {
+        let mut check = cmd(&["pacman", "-Sp", "--noconfirm", "--print-format", "%n", "--config", offline::PACMAN_CONF, "--dbpath", offline::DB_PATH]);
+        check.extend(offline::required_packages(app));
+        steps.push(live_step(
+            "Check offline repository",
+            vec![
+                Action::WriteLiveFile { path: offline::PACMAN_CONF.into(), contents: offline::pacman_conf(repo) },
+                Action::Run(cmd(&["mkdir", "-p", offline::DB_PATH])),
+                Action::Run(cmd(&["pacman", "-Sy", "--config", offline::PACMAN_CONF, "--dbpath", offline::DB_PATH])),
+                Action::Run(check),
+            ],
+        ));
+    }
+
+    if app.format_root == Some(true) {
+        let force = if filesystem == "ext4" { "-F" } else { "-f" };
+        let mkfs = format!("mkfs.{}", filesystem);
+        steps.push(step("Format root partition", vec![Action::Run(cmd(&[&mkfs, force, &root]))]));
+    }
+
+    let mut mount = vec![Action::Mount { source: root.clone(), target: TARGET.into() }];
+    if let Some(efi) = app.efi_partition.as_deref().filter(|p| !p.is_empty()) {
+        mount.push(Action::Mount { source: efi.to_string(), target: format!("{}/boot", TARGET) });
+    }
+    steps.push(live_step("Mount partitions", mount));
+
+    let mut base = cmd(&["pacstrap", "-K"]);
+    if app.offline_repo.is_some() {
+        // The repository is only reachable from the live system, so the
+        // whole package set goes through pacstrap.
+        base.extend(cmd(&["-C", offline::PACMAN_CONF, TARGET]));
+        base.extend(packages.pacstrap.iter().chain(&packages.chroot).cloned());
+    } else {
+        base.push(TARGET.to_string());
+        base.extend(packages.pacstrap.iter().cloned());
+    }
+    if !app.mirrorlist.is_empty() {
+        steps.push(live_step(
+            "Write mirrorlist",
+            vec![Action::WriteLiveFile { path: mirrors::MIRRORLIST.into(), contents: mirrors::render(&app.mirrorlist) }],
+        ));
+    }
+
+    steps.push(step("Install base system", vec![Action::Run(base)]));
+
+    if !app.mirrorlist.is_empty() {
+        steps.push(step(
+            "Copy mirrorlist to target",
+            vec![Action::WriteFile { path: mirrors::MIRRORLIST.into(), contents: mirrors::render(&app.mirrorlist) }],
+        ));
+    }
+
+    steps.push(step(
+        "Generate fstab",
+        vec![Action::Run(cmd(&["sh", "-c", &format!("genfstab -U {0} > {0}/etc/fstab", TARGET)]))],
+    ));
+
+    let mut users = vec![
+        Action::ChrootUnless {
+            check: cmd(&["id", "-u", username]),
+            command: cmd(&["useradd", "-m", "-g", "users", "-G", "wheel", username]),
+        },
+        Action::Chroot(cmd(&["sed", "-i", "/^# %wheel ALL=(ALL\\(:ALL\\)\\?) ALL/s/^# //", "/etc/sudoers"])),
+    ];
+    // Built up inside a Secret so the passwords are never copied into a String.
+    let mut chpasswd = Secret::default();
+    for (user, pw) in [("root", &app.root_password), (username, &app.user_password)] {
+        if let Some(pw) = pw {
+            chpasswd.push_str(user);
+            chpasswd.push_str(":");
+            chpasswd.push_str(pw.expose());
+            chpasswd.push_str("\n");
+        }
+    }
+    if !chpasswd.is_empty() {
+        users.push(Action::ChrootInput(cmd(&["chpasswd"]), chpasswd));
+    }
+    steps.push(step(CREATE_USER_STEP, users));
+
+    steps.push(step(
+        "Set locale",
+        vec![
+            Action::Chroot(cmd(&["sed", "-i", &format!("s/^#{}/{}/", locale, locale), "/etc/locale.gen"])),
+            Action::Chroot(cmd(&["locale-gen"])),
+            Action::WriteFile { path: "/etc/locale.conf".into(), contents: format!("LANG={}\n", locale) },
+        ],
+    ));
+
+    steps.push(step(
+        "Set timezone",
+        vec![
+            Action::Chroot(cmd(&["ln", "-sf", "/usr/share/zoneinfo/UTC", "/etc/localtime"])),
+            Action::Chroot(cmd(&["hwclock", "--systohc"])),
+        ],
+    ));
+
+    steps.push(step(
+        "Set hostname",
+        vec![
+            Action::WriteFile { path: "/etc/hostname".into(), contents: format!("{}\n", hostname) },
+            Action::WriteFile {
+                path: "/etc/hosts".into(),
+                contents: format!(
+                    "127.0.0.1 localhost\n::1       localhost\n127.0.1.1 {0}.localdomain {0}\n",
+                    hostname
+                ),
+            },
+        ],
+    ));
+
+    // Repositories have to be configured before the chroot install so
+    // multilib and custom repository packages resolve in the same run.
+    let mut pacman = Vec::new();
+    let sed = |expr: &str| Action::Chroot(cmd(&["sed", "-i", expr, pacman_conf::PACMAN_CONF]));
+    if app.multilib == Some(true) {
+        pacman.push(sed(pacman_conf::ENABLE_MULTILIB));
+    }
+    if let Some(n) = app.parallel_downloads {
+        pacman.push(sed(&pacman_conf::parallel_downloads(n)));
+    }
+    if app.pacman_color == Some(true) {
+        pacman.push(sed(pacman_conf::ENABLE_COLOR));
+    }
+    // Appending edits are guarded so a resumed install does not repeat them.
+    if app.i_love_candy == Some(true) {
+        pacman.push(Action::ChrootUnless {
+            check: cmd(&["grep", "-q", "^ILoveCandy", pacman_conf::PACMAN_CONF]),
+            command: cmd(&["sed", "-i", pacman_conf::ENABLE_CANDY, pacman_conf::PACMAN_CONF]),
+        });
+    }
+    for repo in &app.custom_repos {
+        // Keys come from a keyserver; offline installs reject keyed repositories on the summary.
+        if let Some(key) = repo.key_id.as_ref().filter(|_| app.offline_repo.is_none()) {
+            pacman.push(Action::Chroot(cmd(&["pacman-key", "--recv-keys", key])));
+            pacman.push(Action::Chroot(cmd(&["pacman-key", "--lsign-key", key])));
+        }
+        pacman.push(Action::ChrootUnless {
+            check: cmd(&["grep", "-qxF", &format!("[{}]", repo.name), pacman_conf::PACMAN_CONF]),
+            command: cmd(&["bash", "-c", "printf '%s' \"$1\" >> \"$2\"", "bash", &repo.section(), pacman_conf::PACMAN_CONF]),
+        });
+    }
+    if !pacman.is_empty() {
+        if app.offline_repo.is_none() {
+            pacman.push(Action::Chroot(cmd(&["pacman", "-Sy"])));
+        }
+        steps.push(step("Configure pacman", pacman));
+    }
+
+    if !packages.chroot.is_empty() && app.offline_repo.is_none() {
+        let mut extra = cmd(&["pacman", "-S", "--noconfirm", "--needed"]);
+        extra.extend(packages.chroot.iter().cloned());
+        steps.push(step("Install additional packages", vec![Action::Chroot(extra)]));
+    }
+
+    if !app.user_groups.is_empty() {
+        steps.push(step(
+            "Add user to groups",
+            vec![Action::Chroot(cmd(&["usermod", "-aG", &app.user_groups.join(","), username]))],
+        ));
+    }
+
+    // AUR builds need the network and makepkg refuses to run as root, so
+    // they run as the created user with a temporary passwordless sudo rule.
+    let aur_helper = app.aur_helper.unwrap_or(AurHelper::None);
+    if let Some(script) = aur_helper.bootstrap_script().filter(|_| app.offline_repo.is_none()) {
+        steps.push(optional_step(
+            "Install AUR helper",
+            vec![Action::Chroot(aur::as_user_with_sudo(username, &["bash", "-c", &script]))],
+        ));
+        if let Some(install) = aur_helper.install_command(&app.aur_packages).filter(|_| !app.aur_packages.is_empty()) {
+            let install: Vec<&str> = install.iter().map(String::as_str).collect();
+            steps.push(optional_step("Install AUR packages", vec![Action::Chroot(aur::as_user_with_sudo(username, &install))]));
+        }
+    }
+
+    if let Some(GpuDriver::Nvidia(driver)) = app.gpu_driver {
+        if driver.needs_kms_setup() {
+            let modules = nvidia::EARLY_KMS_MODULES.join(" ");
+            steps.push(step(
+                "Configure NVIDIA early KMS",
+                vec![
+                    Action::Chroot(cmd(&[
+                        "sed",
+                        "-i",
+                        &format!("s/^MODULES=(\\(.*\\))/MODULES=(\\1 {})/", modules),
+                        "/etc/mkinitcpio.conf",
+                    ])),
+                    // The kms hook would pull nouveau into the initramfs.
+                    Action::Chroot(cmd(&["sed", "-i", "/^HOOKS=/s/ kms//", "/etc/mkinitcpio.conf"])),
+                    Action::WriteFile {
+                        path: nvidia::PACMAN_HOOK_PATH.into(),
+                        contents: nvidia::pacman_hook(driver, kernel),
+                    },
+                ],
+            ));
+        }
+    }
+
+    let desktop = app.desktop_env.unwrap_or(DesktopEnv::None).def();
+    let display_manager = app.display_manager.unwrap_or(DisplayManager::None);
+    let autologin_user = Some(username).filter(|_| app.autologin == Some(true));
+    let mut dm = Vec::new();
+    for (path, contents) in display_manager.config_files(desktop, autologin_user) {
+        dm.push(Action::WriteFile { path, contents });
+    }
+    if let Some(user) = autologin_user.filter(|_| display_manager.supports_autologin(desktop)) {
+        for group in display_manager.autologin_groups() {
+            dm.push(Action::Chroot(cmd(&["groupadd", "-rf", group])));
+            dm.push(Action::Chroot(cmd(&["gpasswd", "-a", user, group])));
+        }
+    }
+    if let Some(service) = display_manager.service() {
+        dm.push(Action::Chroot(cmd(&["systemctl", "enable", service])));
+    }
+    if !dm.is_empty() {
+        steps.push(step("Configure display manager", dm));
+    }
+
+    let network = app.network_stack.unwrap_or(NetworkStack::NetworkManager);
+    let wifi = if app.copy_wifi == Some(true) {
+        network::load_wifi_networks(Path::new(network::IWD_DIR))
+    } else {
+        Vec::new()
+    };
+    let wireless = app.has_wireless() || !wifi.is_empty();
+    let mut net = Vec::new();
+    // Both NetworkManager and iwd ignore files in their private directory
+    // that others can read, static-address keyfiles included.
+    let mut private = false;
+    for (path, contents) in network.config_files(&app.static_addresses) {
+        if path.starts_with(network.private_dir()) {
+            private = true;
+            net.push(Action::WriteSecretFile { path, contents: Secret::new(contents) });
+        } else {
+            net.push(Action::WriteFile { path, contents });
+        }
+    }
+    for (path, contents) in network.wifi_files(&wifi) {
+        private = true;
+        net.push(Action::WriteSecretFile { path, contents });
+    }
+    if private {
+        net.push(Action::Chroot(cmd(&["chmod", "-R", "go-rwx", network.private_dir()])));
+    }
+    if network.uses_resolved() {
+        let resolv = format!("{}/etc/resolv.conf", TARGET);
+        net.push(Action::Run(cmd(&["ln", "-sf", network::RESOLV_STUB, &resolv])));
+    }
+    if !net.is_empty() {
+        steps.push(step("Configure network", net));
+    }
+
+    let mut services = Vec::new();
+    for service in network
+        .services(wireless)
+        .into_iter()
+        .chain(desktop.services.iter().copied())
+        .chain(app.extra_services.iter().map(String::as_str))
+    {
+        services.push(Action::Chroot(cmd(&["systemctl", "enable", service])));
+    }
+    steps.push(step("Enable services", services));
+
+    steps.push(step("Regenerate initramfs", vec![Action::Chroot(cmd(&["mkinitcpio", "-P"]))]));
+
+    if app.install_grub == Some(true) {
+        let mut grub = Vec::new();
+        if uefi {
+            grub.push(Action::Chroot(cmd(&["grub-install", "--target=x86_64-efi", "--efi-directory=/boot", "--bootloader-id=GRUB"])));
+        } else {
+            grub.push(Action::Chroot(cmd(&["grub-install", "--target=i386-pc", &parent_disk(&root)])));
+        }
+        let params = kernel_params(app);
+        if !params.is_empty() {
+            grub.push(Action::Chroot(cmd(&[
+                "sed",
+                "-i",
+                &format!("s/^GRUB_CMDLINE_LINUX_DEFAULT=\"\\(.*\\)\"/GRUB_CMDLINE_LINUX_DEFAULT=\"\\1 {}\"/", params.join(" ")),
+                "/etc/default/grub",
+            ])));
+        }
+        // grub-mkconfig adds any /boot/*-ucode.img as an early initrd; make
+        // sure the image is really there so the entry is not silently missing.
+        if let Some(cpu) = app.cpu_vendor {
+            grub.push(Action::Chroot(cmd(&["test", "-f", cpu.microcode_image()])));
+        }
+        grub.push(Action::Chroot(cmd(&["grub-mkconfig", "-o", "/boot/grub/grub.cfg"])));
+        steps.push(step("Install bootloader", grub));
+    }
+
+    let target_log = format!("{}{}", TARGET, logging::TARGET_LOG_PATH);
+    steps.push(optional_step("Copy install log", vec![Action::Run(cmd(&["install", "-Dm600", logging::LOG_PATH, &target_log]))]));
+
+    steps.push(step("Unmount partitions", vec![Action::Run(cmd(&["umount", "-R", TARGET]))]));
+
+    steps
+}
+
+fn run_command(args: &[String], input: Option<&str>, tx: &Sender<Progress>) -> Result<(), String> {
+    logging::log(Kind::Command, args.join(" "));
+    let _ = tx.send(Progress::Output(format!("$ {}", args.join(" "))));
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("{}: {}", args[0], e))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    for line in String::from_utf8_lossy(&output.stdout).lines().chain(String::from_utf8_lossy(&output.stderr).lines()) {
+        logging::log(Kind::Output, line);
+        let _ = tx.send(Progress::Output(line.to_string()));
+    }
+    if output.status.success() {
+        Ok(())
+    } else {
+        let err = format!("`{}` exited with {}", args.join(" "), output.status);
+        logging::log(Kind::Error, &err);
+        Err(err)
+    }
+}
+
+fn write_file(path: &str, contents: &str) -> Result<(), String> {
+    write_file_mode(path, contents, 0o644)
+}
+
+fn write_file_mode(path: &str, contents: &str, mode: u32) -> Result<(), String> {
+    logging::log(Kind::Command, format!("write {}", path));
+    if let Some(parent) = Path::new(path).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .mode(mode)
+        .open(path)
+        .map_err(|e| format!("{}: {}", path, e))?;
+    file.write_all(contents.as_bytes()).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn run_action(action: &Action, tracker: &mut Tracker, tx: &Sender<Progress>) -> Result<(), String> {
+    match action {
+        Action::Run(args) => {
+            run_command(args, None, tx)?;
+            tracker.observe(args);
+            Ok(())
+        }
+        Action::Chroot(args) => {
+            let mut full = cmd(&["arch-chroot", TARGET]);
+            full.extend(args.iter().cloned());
+            run_command(&full, None, tx)
+        }
+        Action::ChrootInput(args, input) => {
+            let mut full = cmd(&["arch-chroot", TARGET]);
+            full.extend(args.iter().cloned());
+            run_command(&full, Some(input.expose()), tx)
+        }
+        Action::WriteLiveFile { path, contents } => write_file(path, contents),
+        Action::WriteFile { path, contents } => write_file(&format!("{}{}", TARGET, path), contents),
+        Action::WriteSecretFile { path, contents } => write_file_mode(&format!("{}{}", TARGET, path), contents.expose(), 0o600),
+        Action::Mount { source, target } => {
+            if is_mounted(target) {
+                // Not ours to release: it was there before this run started.
+                let _ = tx.send(Progress::Output(format!("{} is already mounted", target)));
+                return Ok(());
+            }
+            fs::create_dir_all(target).map_err(|e| format!("{}: {}", target, e))?;
+            run_command(&cmd(&["mount", source, target]), None, tx)?;
+            tracker.track(Resource::Mount(target.clone()));
+            Ok(())
+        }
+        Action::ChrootUnless { check, command } => {
+            let mut probe = Command::new("arch-chroot");
+            probe.arg(TARGET).args(check).stdout(Stdio::null()).stderr(Stdio::null());
+            if probe.status().is_ok_and(|s| s.success()) {
+                let _ = tx.send(Progress::Output(format!("skipping `{}`: already done", command.join(" "))));
+                return Ok(());
+            }
+            run_action(&Action::Chroot(command.clone()), tracker, tx)
+        }
+    }
+}
+
+fn is_mounted(target: &str) -> bool {
+    let target = target.trim_end_matches('/');
+    fs::read_to_string("/proc/self/mounts")
+        .unwrap_or_default()
+        .lines()
+        .any(|line| line.split_whitespace().nth(1) == Some(target))
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "panicked"
+    }
+}
+
+/// Releases whatever the failed run mounted on the live system.
+fn clean_up(tracker: &mut Tracker, tx: &Sender<Progress>) {
+    let report = tracker.release_all();
+    for released in report.released {
+        logging::log(Kind::Info, format!("cleanup: released {}", released));
+        let _ = tx.send(Progress::Output(format!("cleanup: released {}", released)));
+    }
+    if !report.failed.is_empty() {
+        for failed in &report.failed {
+            logging::log(Kind::Error, format!("cleanup: {}", failed));
+        }
+        let _ = tx.send(Progress::CleanupFailed(report.failed));
+    }
+}
+
+/// Executes the steps in order, reporting progress; stops at the first failure,
+/// or before the next step once `abort` is set.
+/// Steps whose id is in `completed` are skipped unless they must be rerun.
+pub fn run(steps: &[Step], completed: &[String], abort: &AtomicBool, tx: &Sender<Progress>) {
+    let mut tracker = Tracker::default();
+    for (i, step) in steps.iter().enumerate() {
+        if abort.load(Ordering::SeqCst) {
+            logging::log(Kind::Info, format!("aborted before {}", step.name));
+            let _ = tx.send(Progress::Aborted(step.name.clone()));
+            clean_up(&mut tracker, tx);
+            return;
+        }
+        logging::log(Kind::Info, format!("step {}/{}: {}", i + 1, steps.len(), step.name));
+        let _ = tx.send(Progress::StepStarted(i, step.name.clone()));
+        if !step.rerun && completed.contains(&step.id()) {
+            let _ = tx.send(Progress::Output("Completed in an earlier run, skipping".to_string()));
+            continue;
+        }
+        for action in &step.actions {
+            // A bug in an action fails the step like any other error, so the
+            // live system is still cleaned up and the UI hears about it.
+            let result = panic::catch_unwind(AssertUnwindSafe(|| run_action(action, &mut tracker, tx)))
+                .unwrap_or_else(|payload| Err(format!("installer bug: {}", panic_message(payload.as_ref()))));
+            if let Err(err) = result {
+                logging::log(Kind::Error, format!("{} failed: {}", step.name, err));
+                if step.optional {
+                    let _ = tx.send(Progress::Output(format!("warning: {} failed, continuing: {}", step.name, err)));
+                    break;
+                }
+                let _ = tx.send(Progress::Failed(format!("{}: {}", step.name, err)));
+                clean_up(&mut tracker, tx);
+                return;
+            }
+        }
+        let _ = tx.send(Progress::StepDone(step.id()));
+    }
+    logging::log(Kind::Info, "installation finished");
+    let _ = tx.send(Progress::Done);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_parent_disks() {
+        assert_eq!(parent_disk("/dev/sda1"), "/dev/sda");
+        assert_eq!(parent_disk("/dev/vda12"), "/dev/vda");
+        assert_eq!(parent_disk("/dev/nvme0n1p2"), "/dev/nvme0n1");
+        assert_eq!(parent_disk("/dev/mmcblk0p1"), "/dev/mmcblk0");
+        assert_eq!(parent_disk("/dev/sdb"), "/dev/sdb");
+    }
+
+    #[test]
+    fn reports_a_panicking_action_as_a_failure() {
+        let steps = vec![step("Broken", vec![Action::Run(Vec::new())]), step("Never", Vec::new())];
+        let (tx, rx) = std::sync::mpsc::channel();
+        run(&steps, &[], &AtomicBool::new(false), &tx);
+        let failed: Vec<String> = rx
+            .try_iter()
+            .filter_map(|progress| match progress {
+                Progress::Failed(err) => Some(err),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].starts_with("Broken: installer bug: "), "{}", failed[0]);
+    }
+
+    #[test]
+    fn sets_both_passwords_through_chpasswd() {
+        let app = AppState {
+            username: Some("alice".into()),
+            root_password: Some(Secret::new("r00t".into())),
+            user_password: Some(Secret::new("wonder land".into())),
+            ..AppState::default()
+        };
+        let steps = plan(&app);
+        let step = steps.iter().find(|s| s.name == CREATE_USER_STEP).expect("user step");
+        let input = step.actions.iter().find_map(|action| match action {
+            Action::ChrootInput(args, input) if args == &cmd(&["chpasswd"]) => Some(input.expose()),
+            _ => None,
+        });
+        assert_eq!(input, Some("root:r00t\nalice:wonder land\n"));
+    }
+
+    /// (path, mode) of every file the "Configure network" step writes.
+    fn network_files(app: &AppState) -> Vec<(String, u32)> {
+        let steps = plan(app);
+        let step = steps.iter().find(|s| s.name == "Configure network").expect("network step");
+        step.actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::WriteFile { path, .. } => Some((path.clone(), 0o644)),
+                Action::WriteSecretFile { path, .. } => Some((path.clone(), 0o600)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn network_manager_keyfiles_are_private() {
+        let app = AppState {
+            network_stack: Some(NetworkStack::NetworkManager),
+            static_addresses: vec![network::StaticAddress::parse("eth0 192.168.1.10/24 192.168.1.1").unwrap()],
+            copy_wifi: Some(false),
+            ..AppState::default()
+        };
+        assert_eq!(network_files(&app), vec![("/etc/NetworkManager/system-connections/static-eth0.nmconnection".to_string(), 0o600)]);
+    }
+
+    #[test]
+    fn networkd_files_stay_readable() {
+        let app = AppState {
+            network_stack: Some(NetworkStack::Networkd),
+            static_addresses: vec![network::StaticAddress::parse("eth0 192.168.1.10/24").unwrap()],
+            copy_wifi: Some(false),
+            ..AppState::default()
+        };
+        let files = network_files(&app);
+        assert!(!files.is_empty());
+        assert!(files.iter().all(|(_, mode)| *mode == 0o644), "{:?}", files);
+    }
+}
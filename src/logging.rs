@@ -0,0 +1,94 @@
+// Timestamped install log on the live system.
+//
+// One line per event: `<UTC timestamp> <kind> <message>`. The file is
+// copied into the target at the end of the install and into support bundles.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const LOG_PATH: &str = "/var/log/installarch.log";
+/// Where the finished install keeps its copy of the log.
+pub const TARGET_LOG_PATH: &str = "/var/log/installer/install.log";
+
+static LOG: Mutex<Option<File>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Info,
+    Page,
+    Decision,
+    Command,
+    Output,
+    Error,
+}
+
+impl Kind {
+    fn label(&self) -> &'static str {
+        match self {
+            Kind::Info => "info",
+            Kind::Page => "page",
+            Kind::Decision => "decision",
+            Kind::Command => "command",
+            Kind::Output => "output",
+            Kind::Error => "error",
+        }
+    }
+}
+
+/// Opens the log for appending; until this succeeds events are dropped.
+pub fn init() -> Result<(), String> {
+    let path = Path::new(LOG_PATH);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("{}: {}", LOG_PATH, e))?;
+    *LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+    Ok(())
+}
+
+pub fn log(kind: Kind, message: impl AsRef<str>) {
+    let mut log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(file) = log.as_mut() else {
+        return;
+    };
+    let timestamp = timestamp();
+    for line in message.as_ref().lines() {
+        let _ = writeln!(file, "{} {:<8} {}", timestamp, kind.label(), line);
+    }
+}
+
+/// Seconds since the epoch as (year, month, day, hour, minute, second) in UTC.
+fn utc_now() -> (i64, u32, u32, u64, u64, u64) {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil-from-days, valid for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// ISO 8601 UTC, e.g. `2024-05-01T12:00:00Z`.
+pub fn timestamp() -> String {
+    let (y, mo, d, h, mi, s) = utc_now();
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", y, mo, d, h, mi, s)
+}
+
+/// Compact form for file names, e.g. `20240501-120000`.
+pub fn file_timestamp() -> String {
+    let (y, mo, d, h, mi, s) = utc_now();
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", y, mo, d, h, mi, s)
+}
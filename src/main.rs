@@ -1,25 +1,84 @@
 mod app;
+mod aur;
+mod checkpoint;
+mod cleanup;
+mod config;
+mod desktop;
+mod display_manager;
+mod hardware;
+mod install;
+mod logging;
+mod mirrors;
+mod network;
+mod nvidia;
+mod offline;
+mod packages;
+mod pages;
+mod pacman_conf;
+mod preflight;
+mod profiles;
+mod repo;
+mod secret;
+mod summary;
+mod support;
+mod terminal;
 mod ui;
+mod widgets;
+mod wifi;
 
-use crossterm::terminal::{enable_raw_mode, disable_raw_mode};
-use ratatui::backend::CrosstermBackend;
-use ratatui::Terminal;
-use std::io::stdout;
-
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    enable_raw_mode()?;
-    let stdout = stdout();
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+use std::env;
+use std::error::Error;
+use std::path::Path;
 
+/// `prepare-offline <config> <dir>`: fills `dir` with every package the
+/// config needs so a later install can run without network access.
+fn prepare_offline(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [config_path, dir] = args else {
+        return Err("usage: arch_tui_installer prepare-offline <config> <dir>".into());
+    };
     let mut app = app::AppState::default();
+    app.apply_config(&config::load(Path::new(config_path))?)?;
+    let closure = offline::prepare(&app, Path::new(dir))?;
+    println!("{} packages ready in {}", closure.len(), dir);
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("prepare-offline") {
+        return prepare_offline(&args[1..]);
+    }
 
-    let res = ui::run_app(&mut terminal, &mut app);
+    if let Err(err) = logging::init() {
+        eprintln!("Logging disabled: {}", err);
+    }
+    logging::log(logging::Kind::Info, "installer started");
+
+    let mut app = app::AppState::default();
+    app.apply_gpu_probe(hardware::probe_gpus());
+    app.cpu_vendor = hardware::probe_cpu_vendor();
+    app.detected_interfaces = network::probe_interfaces();
+    (app.available_profiles, app.profile_errors) = profiles::load_all();
+    app.available_mirrors = mirrors::load();
+    match checkpoint::Checkpoint::load() {
+        Some(Ok(state)) => match app.apply_config(&state.config) {
+            Ok(()) => {
+                app.page = app::Page::Resume;
+                app.resume = Some(state);
+            }
+            Err(err) => eprintln!("Ignoring {}: {}", checkpoint::STATE_PATH, err),
+        },
+        Some(Err(err)) => eprintln!("Ignoring {}", err),
+        None => {}
+    }
 
-    disable_raw_mode()?;
-    terminal.show_cursor()?;
+    let res = {
+        let mut guard = terminal::TerminalGuard::new()?;
+        ui::run_app(&mut guard.terminal, &mut app)
+    };
     if let Err(err) = res {
-        println!("Error: {:?}", err);
+        logging::log(logging::Kind::Error, format!("installer stopped: {}", err));
+        eprintln!("Error: {} (details in {})", err, logging::LOG_PATH);
     }
 
     Ok(())
@@ -0,0 +1,179 @@
+use std::fs;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+pub const MIRRORLIST: &str = "/etc/pacman.d/mirrorlist";
+const BUNDLED_MIRRORLIST: &str = include_str!("../data/mirrorlist");
+
+/// Mirrors measured per ranking run; the rest keep their original order.
+pub const RANK_LIMIT: usize = 20;
+const RANK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Small file every mirror carries, used for the speed test.
+const RANK_FILE: &str = "core/os/x86_64/core.db";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mirror {
+    pub url: String,
+    pub country: Option<String>,
+    /// Bytes per second measured by `rank`.
+    pub speed: Option<f64>,
+}
+
+impl Mirror {
+    pub fn protocol(&self) -> &str {
+        self.url.split("://").next().unwrap_or_default()
+    }
+
+    pub fn test_url(&self) -> String {
+        let base = self.url.split("$repo").next().unwrap_or(&self.url);
+        format!("{}{}", base, RANK_FILE)
+    }
+}
+
+/// Downloads used to rank mirrors; swap in another client to change transport.
+pub trait HttpClient {
+    /// Fetches `url` and returns the number of bytes received.
+    fn download(&self, url: &str, timeout: Duration) -> Result<u64, String>;
+}
+
+/// `curl` is on every Arch ISO, so the default client shells out to it.
+pub struct CurlClient;
+
+impl HttpClient for CurlClient {
+    fn download(&self, url: &str, timeout: Duration) -> Result<u64, String> {
+        let output = Command::new("curl")
+            .args(["-fsS", "-o", "/dev/null", "-w", "%{size_download}", "--max-time"])
+            .arg(timeout.as_secs().to_string())
+            .arg(url)
+            .output()
+            .map_err(|e| format!("curl: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<f64>()
+            .map(|bytes| bytes as u64)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Parses a pacman mirrorlist. `## Country` headers set the country of the
+/// servers below them; commented-out `#Server =` lines are included too.
+pub fn parse(text: &str) -> Vec<Mirror> {
+    let mut mirrors = Vec::new();
+    let mut country = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix("##") {
+            let header = header.trim();
+            if !header.is_empty() && !header.contains(':') && !header.to_lowercase().contains("mirrorlist") {
+                country = Some(header.to_string());
+            }
+            continue;
+        }
+        let entry = line.trim_start_matches('#').trim();
+        if let Some((key, url)) = entry.split_once('=') {
+            if key.trim() == "Server" {
+                mirrors.push(Mirror { url: url.trim().to_string(), country: country.clone(), speed: None });
+            }
+        }
+    }
+    mirrors
+}
+
+/// Mirrors from the live system's mirrorlist, or the bundled copy.
+pub fn load() -> Vec<Mirror> {
+    let mirrors = fs::read_to_string(MIRRORLIST).map(|text| parse(&text)).unwrap_or_default();
+    if mirrors.is_empty() {
+        parse(BUNDLED_MIRRORLIST)
+    } else {
+        mirrors
+    }
+}
+
+pub fn countries(mirrors: &[Mirror]) -> Vec<String> {
+    let mut countries: Vec<String> = mirrors.iter().filter_map(|m| m.country.clone()).collect();
+    countries.sort();
+    countries.dedup();
+    countries
+}
+
+pub fn filter<'a>(mirrors: &'a [Mirror], country: Option<&str>, protocol: Option<&str>) -> Vec<&'a Mirror> {
+    mirrors
+        .iter()
+        .filter(|m| country.is_none_or(|c| m.country.as_deref() == Some(c)))
+        .filter(|m| protocol.is_none_or(|p| m.protocol() == p))
+        .collect()
+}
+
+/// Measures the first `RANK_LIMIT` mirrors and sorts them fastest first;
+/// mirrors that failed or were not measured go last.
+pub fn rank(mut mirrors: Vec<Mirror>, client: &dyn HttpClient) -> Vec<Mirror> {
+    for mirror in mirrors.iter_mut().take(RANK_LIMIT) {
+        let start = Instant::now();
+        mirror.speed = client
+            .download(&mirror.test_url(), RANK_TIMEOUT)
+            .ok()
+            .map(|bytes| bytes as f64 / start.elapsed().as_secs_f64().max(0.001));
+    }
+    mirrors.sort_by(|a, b| match (a.speed, b.speed) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    mirrors
+}
+
+/// Renders the mirrorlist written to the live system and the target.
+pub fn render(urls: &[String]) -> String {
+    let mut out = String::from("## Generated by the Arch Linux installer\n");
+    for url in urls {
+        out.push_str(&format!("Server = {}\n", url));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commented_servers_with_countries() {
+        let text = "\
+##
+## Arch Linux repository mirrorlist
+## Generated on 2024-01-01
+##
+
+## Germany
+#Server = https://mirror.example.de/archlinux/$repo/os/$arch
+Server = http://ftp.example.de/arch/$repo/os/$arch
+
+## United States
+#Server=https://us.example.com/$repo/os/$arch
+";
+        let mirrors = parse(text);
+        let found: Vec<(&str, Option<&str>)> = mirrors.iter().map(|m| (m.url.as_str(), m.country.as_deref())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("https://mirror.example.de/archlinux/$repo/os/$arch", Some("Germany")),
+                ("http://ftp.example.de/arch/$repo/os/$arch", Some("Germany")),
+                ("https://us.example.com/$repo/os/$arch", Some("United States")),
+            ]
+        );
+        assert!(mirrors.iter().all(|m| m.speed.is_none()));
+    }
+
+    #[test]
+    fn ignores_other_lines() {
+        assert!(parse("# just a comment\nInclude = /etc/pacman.d/other\n").is_empty());
+    }
+
+    #[test]
+    fn bundled_list_is_not_empty() {
+        assert!(!parse(BUNDLED_MIRRORLIST).is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+use crate::app::NetworkStack;
+use crate::secret::Secret;
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+
+const SYS_CLASS_NET: &str = "/sys/class/net";
+/// Where iwd on the live ISO keeps the networks it has joined.
+pub const IWD_DIR: &str = "/var/lib/iwd";
+const NM_CONNECTIONS: &str = "/etc/NetworkManager/system-connections";
+const NETWORKD_DIR: &str = "/etc/systemd/network";
+/// Target of /etc/resolv.conf when systemd-resolved manages DNS.
+pub const RESOLV_STUB: &str = "../run/systemd/resolve/stub-resolv.conf";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub name: String,
+    pub mac: Option<String>,
+    pub wireless: bool,
+}
+
+/// Physical network interfaces of the live system.
+pub fn probe_interfaces() -> Vec<Interface> {
+    let mut interfaces: Vec<Interface> = fs::read_dir(SYS_CLASS_NET)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    // Loopback, bridges and tunnels have no backing device.
+                    if !path.join("device").exists() {
+                        return None;
+                    }
+                    Some(Interface {
+                        mac: fs::read_to_string(path.join("address")).ok().map(|a| a.trim().to_string()),
+                        wireless: path.join("wireless").exists(),
+                        name: entry.file_name().to_string_lossy().into_owned(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+/// A static address for one interface; the address family follows `address`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticAddress {
+    pub interface: String,
+    /// Address with prefix length, e.g. `192.168.1.10/24`.
+    pub address: String,
+    pub gateway: Option<String>,
+    pub dns: Vec<String>,
+}
+
+fn parse_ip(value: &str) -> Result<IpAddr, String> {
+    value.parse().map_err(|_| format!("`{}` is not an IP address", value))
+}
+
+impl StaticAddress {
+    /// Parses `interface address/prefix [gateway|-] [dns...]` as typed on the network page.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split_whitespace();
+        let (Some(interface), Some(address)) = (parts.next(), parts.next()) else {
+            return Err("expected `interface address/prefix [gateway] [dns...]`".to_string());
+        };
+        let (ip, prefix) = address.split_once('/').ok_or_else(|| format!("`{}` is missing a /prefix", address))?;
+        let ip = parse_ip(ip)?;
+        let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+        if !prefix.parse::<u8>().is_ok_and(|p| p <= max_prefix) {
+            return Err(format!("`{}` is not a valid prefix length", prefix));
+        }
+        let gateway = match parts.next() {
+            None | Some("-") => None,
+            Some(gw) => {
+                if parse_ip(gw)?.is_ipv4() != ip.is_ipv4() {
+                    return Err(format!("gateway `{}` is not in the address family of `{}`", gw, address));
+                }
+                Some(gw.to_string())
+            }
+        };
+        let dns = parts.map(|d| parse_ip(d).map(|_| d.to_string())).collect::<Result<_, _>>()?;
+        Ok(StaticAddress { interface: interface.to_string(), address: address.to_string(), gateway, dns })
+    }
+
+    pub fn is_ipv6(&self) -> bool {
+        self.address.contains(':')
+    }
+
+    /// Config syntax: `interface|address|gateway|dns,dns`, empty fields allowed.
+    pub fn key(&self) -> String {
+        format!("{}|{}|{}|{}", self.interface, self.address, self.gateway.as_deref().unwrap_or(""), self.dns.join(","))
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        let mut parts = key.split('|');
+        let (interface, address) = (parts.next()?, parts.next()?);
+        Some(StaticAddress {
+            interface: interface.to_string(),
+            address: address.to_string(),
+            gateway: parts.next().filter(|g| !g.is_empty()).map(str::to_string),
+            dns: parts.next().map(|d| d.split(',').filter(|d| !d.is_empty()).map(str::to_string).collect()).unwrap_or_default(),
+        })
+    }
+}
+
+/// A network the live system has joined, as stored by iwd.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiNetwork {
+    pub ssid: String,
+    /// The iwd profile file name, e.g. `Home.psk`.
+    pub file: String,
+    pub contents: Secret,
+    /// Passphrase, or the raw 64-digit pre-shared key; `None` for open networks.
+    pub psk: Option<Secret>,
+}
+
+/// iwd stores SSIDs with characters outside `[A-Za-z0-9 _-]` as `=` and hex.
+fn decode_ssid(stem: &str) -> Option<String> {
+    match stem.strip_prefix('=') {
+        Some(hex) => {
+            let bytes = (0..hex.len())
+                .step_by(2)
+                .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+                .collect::<Option<Vec<u8>>>()?;
+            String::from_utf8(bytes).ok()
+        }
+        None => Some(stem.to_string()),
+    }
+}
+
+/// The iwd profile file stem for `ssid`; the inverse of `decode_ssid`.
+pub fn encode_ssid(ssid: &str) -> String {
+    if ssid.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '_' || c == '-') {
+        ssid.to_string()
+    } else {
+        format!("={}", ssid.bytes().map(|b| format!("{:02x}", b)).collect::<String>())
+    }
+}
+
+/// Loads the personal and open networks iwd has saved in `dir`.
+pub fn load_wifi_networks(dir: &Path) -> Vec<WifiNetwork> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|p| p.extension().is_some_and(|ext| ext == "psk" || ext == "open"));
+    paths.sort();
+
+    let mut networks = Vec::new();
+    for path in paths {
+        let (Some(stem), Some(file)) = (path.file_stem(), path.file_name()) else {
+            continue;
+        };
+        let (Some(ssid), Ok(contents)) = (decode_ssid(&stem.to_string_lossy()), fs::read_to_string(&path)) else {
+            continue;
+        };
+        let value = |key: &str| contents.lines().find_map(|l| l.trim().strip_prefix(key)).map(|v| v.trim().to_string());
+        let psk = value("Passphrase=").or_else(|| value("PreSharedKey=")).map(Secret::new);
+        networks.push(WifiNetwork { ssid, file: file.to_string_lossy().into_owned(), psk, contents: Secret::new(contents) });
+    }
+    networks
+}
+
+fn networkd_file(name: &str, match_section: &str, network: &[String]) -> (String, String) {
+    (
+        format!("{}/{}.network", NETWORKD_DIR, name),
+        format!("[Match]\n{}\n\n[Network]\n{}\n", match_section, network.join("\n")),
+    )
+}
+
+fn nm_ip_section(family: &str, addresses: &[&StaticAddress]) -> String {
+    if addresses.is_empty() {
+        return format!("[{}]\nmethod=auto\n", family);
+    }
+    let mut section = format!("[{}]\nmethod=manual\n", family);
+    for (i, addr) in addresses.iter().enumerate() {
+        match &addr.gateway {
+            Some(gw) => section.push_str(&format!("address{}={},{}\n", i + 1, addr.address, gw)),
+            None => section.push_str(&format!("address{}={}\n", i + 1, addr.address)),
+        }
+    }
+    let dns: Vec<&str> = addresses.iter().flat_map(|a| &a.dns).map(String::as_str).collect();
+    if !dns.is_empty() {
+        section.push_str(&format!("dns={};\n", dns.join(";")));
+    }
+    section
+}
+
+impl NetworkStack {
+    pub const ALL: [NetworkStack; 3] = [NetworkStack::NetworkManager, NetworkStack::Networkd, NetworkStack::Iwd];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NetworkStack::NetworkManager => "NetworkManager",
+            NetworkStack::Networkd => "systemd-networkd",
+            NetworkStack::Iwd => "iwd (Wi-Fi) + systemd-networkd (wired)",
+        }
+    }
+
+    pub fn packages(&self, wireless: bool) -> &'static [&'static str] {
+        match self {
+            NetworkStack::NetworkManager => &["networkmanager"],
+            // networkd has no Wi-Fi authentication of its own.
+            NetworkStack::Networkd if wireless => &["iwd"],
+            NetworkStack::Networkd => &[],
+            NetworkStack::Iwd => &["iwd"],
+        }
+    }
+
+    pub fn services(&self, wireless: bool) -> Vec<&'static str> {
+        match self {
+            NetworkStack::NetworkManager => vec!["NetworkManager"],
+            NetworkStack::Networkd if wireless => vec!["systemd-networkd", "systemd-resolved", "iwd"],
+            NetworkStack::Networkd => vec!["systemd-networkd", "systemd-resolved"],
+            NetworkStack::Iwd => vec!["systemd-networkd", "systemd-resolved", "iwd"],
+        }
+    }
+
+    /// systemd-resolved provides DNS for every stack except NetworkManager.
+    pub fn uses_resolved(&self) -> bool {
+        *self != NetworkStack::NetworkManager
+    }
+
+    /// Directory holding files that must only be readable by root.
+    pub fn private_dir(&self) -> &'static str {
+        match self {
+            NetworkStack::NetworkManager => NM_CONNECTIONS,
+            _ => IWD_DIR,
+        }
+    }
+
+    /// Interface configuration written into the target as (path, contents).
+    pub fn config_files(&self, addresses: &[StaticAddress]) -> Vec<(String, String)> {
+        let mut interfaces: Vec<&str> = Vec::new();
+        for addr in addresses {
+            if !interfaces.contains(&addr.interface.as_str()) {
+                interfaces.push(&addr.interface);
+            }
+        }
+        let mut files = Vec::new();
+        for interface in interfaces {
+            let addrs: Vec<&StaticAddress> = addresses.iter().filter(|a| a.interface == interface).collect();
+            match self {
+                NetworkStack::NetworkManager => {
+                    let (v6, v4): (Vec<&StaticAddress>, Vec<&StaticAddress>) = addrs.iter().partition(|a| a.is_ipv6());
+                    files.push((
+                        format!("{}/static-{}.nmconnection", NM_CONNECTIONS, interface),
+                        format!(
+                            "[connection]\nid=static-{0}\ntype=ethernet\ninterface-name={0}\n\n{1}\n{2}",
+                            interface,
+                            nm_ip_section("ipv4", &v4),
+                            nm_ip_section("ipv6", &v6)
+                        ),
+                    ));
+                }
+                NetworkStack::Networkd | NetworkStack::Iwd => {
+                    let mut network = Vec::new();
+                    for addr in &addrs {
+                        network.push(format!("Address={}", addr.address));
+                        if let Some(gw) = &addr.gateway {
+                            network.push(format!("Gateway={}", gw));
+                        }
+                        network.extend(addr.dns.iter().map(|d| format!("DNS={}", d)));
+                    }
+                    files.push(networkd_file(&format!("20-{}", interface), &format!("Name={}", interface), &network));
+                }
+            }
+        }
+        // Everything without a static address falls back to DHCP; networkd
+        // applies the first matching file, so the static ones above win.
+        match self {
+            NetworkStack::NetworkManager => {}
+            NetworkStack::Networkd => files.push(networkd_file("80-dhcp", "Type=ether wlan", &["DHCP=yes".to_string()])),
+            NetworkStack::Iwd => {
+                files.push(networkd_file("80-dhcp", "Type=ether", &["DHCP=yes".to_string()]));
+                files.push((
+                    "/etc/iwd/main.conf".to_string(),
+                    "[General]\nEnableNetworkConfiguration=true\n\n[Network]\nNameResolvingService=systemd\n".to_string(),
+                ));
+            }
+        }
+        files
+    }
+
+    /// The live system's saved Wi-Fi networks in the form this stack reads.
+    pub fn wifi_files(&self, networks: &[WifiNetwork]) -> Vec<(String, Secret)> {
+        networks
+            .iter()
+            .map(|net| match self {
+                NetworkStack::NetworkManager => {
+                    let mut contents = Secret::new(format!(
+                        "[connection]\nid={0}\ntype=wifi\n\n[wifi]\nmode=infrastructure\nssid={0}\n\n",
+                        net.ssid
+                    ));
+                    if let Some(psk) = &net.psk {
+                        contents.push_str("[wifi-security]\nkey-mgmt=wpa-psk\npsk=");
+                        contents.push_str(psk.expose());
+                        contents.push_str("\n\n");
+                    }
+                    contents.push_str("[ipv4]\nmethod=auto\n\n[ipv6]\nmethod=auto\n");
+                    (format!("{}/{}.nmconnection", NM_CONNECTIONS, net.ssid.replace('/', "_")), contents)
+                }
+                NetworkStack::Networkd | NetworkStack::Iwd => (format!("{}/{}", IWD_DIR, net.file), net.contents.clone()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_static_address() {
+        let addr = StaticAddress::parse("eth0 192.168.1.10/24 192.168.1.1 1.1.1.1 9.9.9.9").unwrap();
+        assert_eq!(
+            addr,
+            StaticAddress {
+                interface: "eth0".into(),
+                address: "192.168.1.10/24".into(),
+                gateway: Some("192.168.1.1".into()),
+                dns: vec!["1.1.1.1".into(), "9.9.9.9".into()],
+            }
+        );
+        assert!(!addr.is_ipv6());
+        assert_eq!(StaticAddress::from_key(&addr.key()), Some(addr));
+    }
+
+    #[test]
+    fn parses_ipv6_without_gateway() {
+        let addr = StaticAddress::parse("eth0 2001:db8::10/64 - 2001:4860:4860::8888").unwrap();
+        assert!(addr.is_ipv6());
+        assert_eq!(addr.gateway, None);
+        assert_eq!(addr.dns, vec!["2001:4860:4860::8888"]);
+        assert_eq!(StaticAddress::from_key(&addr.key()), Some(addr));
+    }
+
+    #[test]
+    fn rejects_bad_static_addresses() {
+        assert!(StaticAddress::parse("eth0").is_err());
+        assert!(StaticAddress::parse("eth0 192.168.1.10").is_err());
+        assert!(StaticAddress::parse("eth0 192.168.1.10/33").is_err());
+        assert!(StaticAddress::parse("eth0 192.168.1.300/24").is_err());
+        assert!(StaticAddress::parse("eth0 192.168.1.10/24 2001:db8::1").is_err());
+        assert!(StaticAddress::parse("eth0 192.168.1.10/24 - dns.example").is_err());
+    }
+
+    #[test]
+    fn ssid_file_names_round_trip() {
+        assert_eq!(encode_ssid("Home Net_2-G"), "Home Net_2-G");
+        assert_eq!(encode_ssid("Café"), "=436166c3a9");
+        for ssid in ["Home", "Café", "a/b.c"] {
+            assert_eq!(decode_ssid(&encode_ssid(ssid)).as_deref(), Some(ssid));
+        }
+    }
+}
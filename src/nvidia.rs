@@ -0,0 +1,74 @@
+use crate::app::{KernelSelection, NvidiaDriver};
+
+/// Modules loaded from the initramfs so the NVIDIA DRM device exists before
+/// the display manager starts (early KMS).
+pub const EARLY_KMS_MODULES: [&str; 4] = ["nvidia", "nvidia_modeset", "nvidia_uvm", "nvidia_drm"];
+
+pub const KERNEL_PARAMS: [&str; 1] = ["nvidia-drm.modeset=1"];
+
+pub const PACMAN_HOOK_PATH: &str = "/etc/pacman.d/hooks/nvidia.hook";
+
+impl NvidiaDriver {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NvidiaDriver::Proprietary => "NVIDIA (proprietary)",
+            NvidiaDriver::Open => "NVIDIA (open kernel modules)",
+            NvidiaDriver::Dkms => "NVIDIA (DKMS, any kernel)",
+            NvidiaDriver::Nouveau => "NVIDIA (nouveau)",
+        }
+    }
+
+    /// The kernel module package(s), matched to the selected kernels.
+    pub fn module_packages(&self, kernel: KernelSelection) -> Vec<&'static str> {
+        match self {
+            NvidiaDriver::Proprietary => kernel
+                .kernels()
+                .iter()
+                .map(|k| match *k {
+                    "linux-lts" => "nvidia-lts",
+                    _ => "nvidia",
+                })
+                .collect(),
+            // nvidia-open is only prebuilt for the stock kernel.
+            NvidiaDriver::Open if kernel == KernelSelection::Linux => vec!["nvidia-open"],
+            NvidiaDriver::Open => vec!["nvidia-open-dkms"],
+            NvidiaDriver::Dkms => vec!["nvidia-dkms"],
+            NvidiaDriver::Nouveau => Vec::new(),
+        }
+    }
+
+    pub fn packages(&self, kernel: KernelSelection) -> Vec<&'static str> {
+        let mut pkgs = self.module_packages(kernel);
+        match self {
+            NvidiaDriver::Nouveau => pkgs.extend(["mesa", "vulkan-nouveau"]),
+            _ => pkgs.extend(["nvidia-utils", "nvidia-settings"]),
+        }
+        pkgs
+    }
+
+    /// Whether the proprietary-style kernel setup (modeset, early KMS, hook) applies.
+    pub fn needs_kms_setup(&self) -> bool {
+        !matches!(self, NvidiaDriver::Nouveau)
+    }
+}
+
+/// Pacman hook that regenerates the initramfs whenever the driver or a kernel
+/// changes, so the early-loaded modules never go out of sync.
+pub fn pacman_hook(driver: NvidiaDriver, kernel: KernelSelection) -> String {
+    let mut targets: Vec<&str> = driver.module_packages(kernel);
+    targets.extend(kernel.kernels());
+
+    let mut hook = String::from("[Trigger]\nOperation=Install\nOperation=Upgrade\nOperation=Remove\nType=Package\n");
+    for target in targets {
+        hook.push_str(&format!("Target={}\n", target));
+    }
+    hook.push_str(
+        "\n[Action]\n\
+         Description=Update NVIDIA module in initcpio\n\
+         Depends=mkinitcpio\n\
+         When=PostTransaction\n\
+         NeedsTargets\n\
+         Exec=/bin/sh -c 'while read -r trg; do case $trg in linux*) exit 0; esac; done; /usr/bin/mkinitcpio -P'\n",
+    );
+    hook
+}
@@ -0,0 +1,103 @@
+use crate::app::AppState;
+use crate::packages;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+pub const REPO_NAME: &str = "offline";
+/// pacman.conf on the live system that points only at the offline repository.
+pub const PACMAN_CONF: &str = "/tmp/installarch-offline.conf";
+/// Scratch database directory so the live system's own database is untouched.
+pub const DB_PATH: &str = "/tmp/installarch-offline-db";
+const CLOSURE_FILE: &str = "closure.txt";
+
+/// Accepts a plain directory or a `file://` URL and returns the pacman
+/// `Server` value for it.
+pub fn server(source: &str) -> String {
+    if source.contains("://") {
+        source.trim_end_matches('/').to_string()
+    } else {
+        format!("file://{}", source.trim_end_matches('/'))
+    }
+}
+
+pub fn pacman_conf(source: &str) -> String {
+    format!(
+        "[options]\nArchitecture = auto\nSigLevel = Required DatabaseOptional\nLocalFileSigLevel = Optional\n\n[{}]\nServer = {}\n",
+        REPO_NAME,
+        server(source)
+    )
+}
+
+/// Every package the install will request, pacstrap and chroot sets combined.
+pub fn required_packages(app: &AppState) -> Vec<String> {
+    let set = packages::resolve(app);
+    set.pacstrap.into_iter().chain(set.chroot).collect()
+}
+
+fn run(args: &[&str]) -> Result<String, String> {
+    println!("$ {}", args.join(" "));
+    let output = Command::new(args[0])
+        .args(&args[1..])
+        .output()
+        .map_err(|e| format!("{}: {}", args[0], e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!("`{}` failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Resolves the full dependency closure of `app`'s package set against the
+/// live system's repositories, then fills `dir` with the packages and a
+/// repository database so it can serve as the offline repository.
+pub fn prepare(app: &AppState, dir: &Path) -> Result<Vec<String>, String> {
+    let mut pkgs = required_packages(app);
+    // The target's hardware is unknown while preparing.
+    for pkg in packages::hardware_dependent(app) {
+        if !pkgs.iter().any(|p| p == pkg) {
+            pkgs.push(pkg.to_string());
+        }
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let dir_str = dir.to_string_lossy().into_owned();
+    let db_path = dir.join(".sync-db");
+    fs::create_dir_all(&db_path).map_err(|e| format!("{}: {}", db_path.display(), e))?;
+    let db_path = db_path.to_string_lossy().into_owned();
+
+    run(&["pacman", "-Sy", "--dbpath", &db_path])?;
+
+    let mut print = vec!["pacman", "-Sp", "--noconfirm", "--print-format", "%n %l", "--dbpath", &db_path];
+    print.extend(pkgs.iter().map(String::as_str));
+    let targets = run(&print)?;
+
+    let mut closure = Vec::new();
+    for line in targets.lines() {
+        let Some((name, location)) = line.split_once(' ') else {
+            continue;
+        };
+        closure.push(name.to_string());
+        // Reuse anything already in the live system's cache instead of downloading it again.
+        let file = location.rsplit('/').next().unwrap_or_default();
+        let cached = Path::new("/var/cache/pacman/pkg").join(file);
+        if !file.is_empty() && cached.is_file() && !dir.join(file).exists() {
+            fs::copy(&cached, dir.join(file)).map_err(|e| format!("{}: {}", cached.display(), e))?;
+            let sig = cached.with_file_name(format!("{}.sig", file));
+            if sig.is_file() {
+                let _ = fs::copy(&sig, dir.join(format!("{}.sig", file)));
+            }
+        }
+    }
+
+    let mut download = vec!["pacman", "-Sw", "--noconfirm", "--dbpath", &db_path, "--cachedir", &dir_str];
+    download.extend(pkgs.iter().map(String::as_str));
+    run(&download)?;
+
+    let db = format!("{}/{}.db.tar.gz", dir_str, REPO_NAME);
+    run(&["sh", "-c", &format!("repo-add --include-sigs '{}' '{}'/*.pkg.tar.*[!g]", db, dir_str)])?;
+
+    fs::write(dir.join(CLOSURE_FILE), closure.join("\n") + "\n").map_err(|e| e.to_string())?;
+    let _ = fs::remove_dir_all(dir.join(".sync-db"));
+    Ok(closure)
+}
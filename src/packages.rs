@@ -0,0 +1,178 @@
+use crate::app::{AppState, AurHelper, DesktopEnv, DisplayManager, GpuDriver, KernelSelection, NetworkStack, NvidiaDriver};
+use crate::aur;
+use crate::hardware::{self, CpuVendor, GpuVendor};
+
+/// Packages to install, split between the `pacstrap` run that creates the
+/// target and the `pacman -S` run inside the chroot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageSet {
+    pub pacstrap: Vec<String>,
+    pub chroot: Vec<String>,
+}
+
+impl PackageSet {
+    fn contains(&self, pkg: &str) -> bool {
+        self.pacstrap.iter().chain(self.chroot.iter()).any(|p| p == pkg)
+    }
+
+    pub fn add_pacstrap<'a>(&mut self, pkgs: impl IntoIterator<Item = &'a str>) {
+        for pkg in pkgs {
+            if !self.contains(pkg) {
+                self.pacstrap.push(pkg.to_string());
+            }
+        }
+    }
+
+    pub fn add_chroot<'a>(&mut self, pkgs: impl IntoIterator<Item = &'a str>) {
+        for pkg in pkgs {
+            if !self.contains(pkg) {
+                self.chroot.push(pkg.to_string());
+            }
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.pacstrap.len() + self.chroot.len()
+    }
+}
+
+pub fn filesystem_packages(filesystem: &str) -> &'static [&'static str] {
+    match filesystem {
+        "btrfs" => &["btrfs-progs"],
+        "xfs" => &["xfsprogs"],
+        _ => &["e2fsprogs"],
+    }
+}
+
+fn gpu_vendor_packages(vendor: GpuVendor) -> &'static [&'static str] {
+    match vendor {
+        GpuVendor::Intel => &["mesa", "vulkan-intel", "intel-media-driver"],
+        GpuVendor::Amd => &["mesa", "vulkan-radeon", "libva-mesa-driver"],
+        GpuVendor::Nvidia | GpuVendor::Other(_) => &[],
+    }
+}
+
+/// Packages `resolve` only adds for hardware of the machine it runs on: CPU
+/// microcode, `efibootmgr` on UEFI, Wi-Fi support and the integrated GPU
+/// stack of hybrid laptops. An offline repository is prepared on another
+/// machine than the target, so it carries all of them.
+pub fn hardware_dependent(app: &AppState) -> Vec<&'static str> {
+    let mut pkgs: Vec<&'static str> = vec![CpuVendor::Intel.microcode_package(), CpuVendor::Amd.microcode_package()];
+    if app.install_grub == Some(true) {
+        pkgs.push("efibootmgr");
+    }
+    pkgs.extend(app.network_stack.unwrap_or(NetworkStack::NetworkManager).packages(true));
+    for vendor in [GpuVendor::Intel, GpuVendor::Amd] {
+        pkgs.extend(gpu_vendor_packages(vendor));
+    }
+    pkgs
+}
+
+/// Computes the deduplicated package set for the current answers.
+pub fn resolve(app: &AppState) -> PackageSet {
+    let kernel = app.kernel.unwrap_or(KernelSelection::Both);
+    let mut set = PackageSet::default();
+
+    set.add_pacstrap(["base", "base-devel", "linux-firmware"]);
+    for k in kernel.kernels() {
+        set.add_pacstrap([*k, &format!("{}-headers", k)]);
+    }
+    if let Some(cpu) = app.cpu_vendor {
+        set.add_pacstrap([cpu.microcode_package()]);
+    }
+    set.add_pacstrap(filesystem_packages(app.filesystem.as_deref().unwrap_or("ext4")).iter().copied());
+    if app.efi_partition.as_deref().is_some_and(|p| !p.is_empty()) {
+        set.add_pacstrap(["dosfstools", "mtools"]);
+    }
+    set.add_pacstrap(["sudo", "nano", "openssh"]);
+    let network = app.network_stack.unwrap_or(NetworkStack::NetworkManager);
+    set.add_pacstrap(network.packages(app.has_wireless()).iter().copied());
+
+    match app.gpu_driver.unwrap_or(GpuDriver::None) {
+        GpuDriver::Intel => set.add_chroot(gpu_vendor_packages(GpuVendor::Intel).iter().copied()),
+        GpuDriver::AMD => set.add_chroot(gpu_vendor_packages(GpuVendor::Amd).iter().copied()),
+        GpuDriver::Nvidia(driver) => set.add_chroot(driver.packages(kernel)),
+        GpuDriver::None => {}
+    }
+    // 32-bit userspace drivers for Steam and Wine, once multilib is enabled.
+    if app.multilib == Some(true) {
+        match app.gpu_driver.unwrap_or(GpuDriver::None) {
+            GpuDriver::Intel => set.add_chroot(["lib32-mesa", "lib32-vulkan-intel"]),
+            GpuDriver::AMD => set.add_chroot(["lib32-mesa", "lib32-vulkan-radeon"]),
+            GpuDriver::Nvidia(NvidiaDriver::Nouveau) => set.add_chroot(["lib32-mesa", "lib32-vulkan-nouveau"]),
+            GpuDriver::Nvidia(_) => set.add_chroot(["lib32-nvidia-utils"]),
+            GpuDriver::None => {}
+        }
+    }
+    // The integrated half of a hybrid laptop still needs its mesa stack.
+    if hardware::is_hybrid(&app.detected_gpus) {
+        for gpu in &app.detected_gpus {
+            set.add_chroot(gpu_vendor_packages(gpu.vendor).iter().copied());
+        }
+    }
+
+    let desktop = app.desktop_env.unwrap_or(DesktopEnv::None);
+    set.add_chroot(desktop.packages(app.desktop_full == Some(true)));
+    set.add_chroot(app.display_manager.unwrap_or(DisplayManager::None).packages().iter().copied());
+
+    set.add_chroot(app.extra_packages.iter().map(String::as_str));
+    if app.aur_helper.is_some_and(|h| h != AurHelper::None) {
+        set.add_chroot(aur::BUILD_DEPENDS);
+    }
+
+    if app.install_grub == Some(true) {
+        set.add_chroot(["grub", "os-prober"]);
+        if hardware::is_uefi() {
+            set.add_chroot(["efibootmgr"]);
+        }
+    }
+
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::GpuDevice;
+    use crate::network::Interface;
+
+    fn gpu(vendor: GpuVendor) -> GpuDevice {
+        GpuDevice { vendor, vendor_id: 0, device_id: 0, name: String::new() }
+    }
+
+    /// What the preparing machine resolves plus the hardware-dependent
+    /// packages covers whatever the target machine resolves.
+    #[test]
+    fn offline_set_covers_any_target_hardware() {
+        let answers = AppState {
+            install_grub: Some(true),
+            network_stack: Some(NetworkStack::Networkd),
+            gpu_driver: Some(GpuDriver::Nvidia(NvidiaDriver::Open)),
+            ..AppState::default()
+        };
+        let mut prepared = resolve(&answers);
+        prepared.add_chroot(hardware_dependent(&answers));
+
+        let target = AppState {
+            cpu_vendor: Some(CpuVendor::Amd),
+            detected_gpus: vec![gpu(GpuVendor::Amd), gpu(GpuVendor::Nvidia)],
+            detected_interfaces: vec![Interface { name: "wlan0".into(), mac: None, wireless: true }],
+            ..answers
+        };
+        let target = resolve(&target);
+        let missing: Vec<&String> = target.pacstrap.iter().chain(&target.chroot).filter(|p| !prepared.contains(p)).collect();
+        assert!(missing.is_empty(), "missing {:?}", missing);
+        assert!(prepared.contains("efibootmgr"));
+        assert!(prepared.contains("iwd"));
+    }
+
+    #[test]
+    fn sets_do_not_repeat_packages() {
+        let mut set = PackageSet::default();
+        set.add_pacstrap(["base", "linux"]);
+        set.add_chroot(["linux", "vim", "vim"]);
+        assert_eq!(set.pacstrap, vec!["base", "linux"]);
+        assert_eq!(set.chroot, vec!["vim"]);
+        assert_eq!(set.total(), 3);
+    }
+}
@@ -0,0 +1,65 @@
+pub const PACMAN_CONF: &str = "/etc/pacman.conf";
+
+/// An extra repository appended to the target's pacman.conf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomRepo {
+    pub name: String,
+    pub server: String,
+    /// Signing key fetched and locally signed with pacman-key.
+    pub key_id: Option<String>,
+}
+
+impl CustomRepo {
+    /// Parses `name server [key-id]` as typed on the pacman page.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split_whitespace();
+        let (Some(name), Some(server)) = (parts.next(), parts.next()) else {
+            return Err("expected `name server [key-id]`".to_string());
+        };
+        if !server.contains("://") {
+            return Err(format!("`{}` is not a URL", server));
+        }
+        Ok(CustomRepo {
+            name: name.to_string(),
+            server: server.to_string(),
+            key_id: parts.next().map(str::to_string),
+        })
+    }
+
+    /// Config syntax: `name|server|key-id`, the key being optional.
+    pub fn key(&self) -> String {
+        format!("{}|{}|{}", self.name, self.server, self.key_id.as_deref().unwrap_or(""))
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        let mut parts = key.split('|');
+        let (name, server) = (parts.next()?, parts.next()?);
+        Some(CustomRepo {
+            name: name.to_string(),
+            server: server.to_string(),
+            key_id: parts.next().filter(|k| !k.is_empty()).map(str::to_string),
+        })
+    }
+
+    /// The section appended to pacman.conf. Unsigned repositories cannot be
+    /// verified, so they are trusted as-is.
+    pub fn section(&self) -> String {
+        let sig_level = if self.key_id.is_some() { "Required" } else { "Optional TrustAll" };
+        format!("\n[{}]\nSigLevel = {}\nServer = {}\n", self.name, sig_level, self.server)
+    }
+}
+
+/// sed expressions applied to pacman.conf.
+pub const ENABLE_MULTILIB: &str = "/^#\\[multilib\\]/,/^#Include/ s/^#//";
+pub const ENABLE_COLOR: &str = "s/^#Color/Color/";
+pub const ENABLE_CANDY: &str = "/^\\[options\\]/a ILoveCandy";
+
+/// Values offered for `ParallelDownloads`.
+pub const PARALLEL_DOWNLOADS: std::ops::RangeInclusive<u32> = 1..=20;
+
+/// Shown and preselected when the answers leave `ParallelDownloads` unset.
+pub const DEFAULT_PARALLEL_DOWNLOADS: u32 = 5;
+
+pub fn parallel_downloads(n: u32) -> String {
+    format!("s/^#\\?ParallelDownloads.*/ParallelDownloads = {}/", n)
+}
@@ -0,0 +1,159 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, DesktopEnv, DisplayManager, Page};
+use crate::widgets::{ListEvent, SelectList};
+use crossterm::event::KeyEvent;
+use ratatui::{layout::Rect, Frame};
+
+pub struct Desktop {
+    list: SelectList<DesktopEnv>,
+}
+
+impl Default for Desktop {
+    fn default() -> Self {
+        Desktop { list: SelectList::new(DesktopEnv::ALL.to_vec()) }
+    }
+}
+
+impl PageView for Desktop {
+    fn page(&self) -> Page {
+        Page::DesktopEnv
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.list.select(&app.desktop_env.unwrap_or(DesktopEnv::None));
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        self.list.render(f, area, "Desktop Environment");
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(desktop) => {
+                app.set_desktop_env(desktop);
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
+
+pub struct Extras {
+    list: SelectList<&'static str>,
+}
+
+impl Default for Extras {
+    fn default() -> Self {
+        Extras { list: SelectList::new(vec!["Minimal", "Full"]) }
+    }
+}
+
+impl PageView for Extras {
+    fn page(&self) -> Page {
+        Page::DesktopExtras
+    }
+
+    /// Extras only exist for an actual desktop.
+    fn is_applicable(&self, app: &AppState) -> bool {
+        app.desktop_env.unwrap_or(DesktopEnv::None) != DesktopEnv::None
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.list.select(if app.desktop_full == Some(true) { &"Full" } else { &"Minimal" });
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let desktop = app.desktop_env.unwrap_or(DesktopEnv::None).def();
+        let title = format!("{} extras (Full also installs {})", desktop.label, desktop.extras.join(" "));
+        self.list.render(f, area, &title);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(choice) => {
+                app.desktop_full = Some(choice == "Full");
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
+
+pub struct DisplayManagerPage {
+    list: SelectList<DisplayManager>,
+}
+
+impl Default for DisplayManagerPage {
+    fn default() -> Self {
+        DisplayManagerPage { list: SelectList::new(DisplayManager::ALL.to_vec()) }
+    }
+}
+
+impl PageView for DisplayManagerPage {
+    fn page(&self) -> Page {
+        Page::DisplayManager
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.list.select(&app.display_manager.unwrap_or(DisplayManager::None));
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        self.list.render(f, area, "Display Manager");
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(dm) => {
+                app.display_manager = Some(dm);
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
+
+pub struct Autologin {
+    list: SelectList<&'static str>,
+}
+
+impl Default for Autologin {
+    fn default() -> Self {
+        Autologin { list: SelectList::new(vec!["Yes", "No"]) }
+    }
+}
+
+impl PageView for Autologin {
+    fn page(&self) -> Page {
+        Page::Autologin
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.list.select(if app.autologin == Some(true) { &"Yes" } else { &"No" });
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let user = app.username.as_deref().unwrap_or("user");
+        let desktop = app.desktop_env.unwrap_or(DesktopEnv::None).def();
+        let title = if app.display_manager.unwrap_or(DisplayManager::None).supports_autologin(desktop) {
+            format!("Log in automatically as '{}'?", user)
+        } else {
+            "Autologin is not supported by this display manager".to_string()
+        };
+        self.list.render(f, area, &title);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(answer) => {
+                app.autologin = Some(answer == "Yes");
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
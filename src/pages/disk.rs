@@ -0,0 +1,83 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, Page};
+use crate::widgets::{ListEvent, SelectList};
+use crossterm::event::KeyEvent;
+use ratatui::{layout::Rect, Frame};
+
+/// A Yes/No question whose answer is read by `get` and stored by `set`.
+pub struct YesNo {
+    page: Page,
+    title: &'static str,
+    get: fn(&AppState) -> Option<bool>,
+    set: fn(&mut AppState, bool),
+    list: SelectList<&'static str>,
+}
+
+impl YesNo {
+    pub fn new(page: Page, title: &'static str, get: fn(&AppState) -> Option<bool>, set: fn(&mut AppState, bool)) -> Self {
+        YesNo { page, title, get, set, list: SelectList::new(vec!["Yes", "No"]) }
+    }
+}
+
+impl PageView for YesNo {
+    fn page(&self) -> Page {
+        self.page
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.list.select(if (self.get)(app) == Some(false) { &"No" } else { &"Yes" });
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        self.list.render(f, area, self.title);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(answer) => {
+                (self.set)(app, answer == "Yes");
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
+
+const FILESYSTEMS: [&str; 3] = ["ext4", "btrfs", "xfs"];
+
+pub struct Filesystem {
+    list: SelectList<&'static str>,
+}
+
+impl Default for Filesystem {
+    fn default() -> Self {
+        Filesystem { list: SelectList::new(FILESYSTEMS.to_vec()) }
+    }
+}
+
+impl PageView for Filesystem {
+    fn page(&self) -> Page {
+        Page::Filesystem
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        let current = FILESYSTEMS.iter().find(|fs| app.filesystem.as_deref() == Some(**fs));
+        self.list.select(current.unwrap_or(&FILESYSTEMS[0]));
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        self.list.render(f, area, "Filesystem type");
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(fs) => {
+                app.filesystem = Some(fs.to_string());
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, Page};
+use crate::checkpoint::{self, Checkpoint};
+use crate::install::{self, Progress, Step};
+use crate::support;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// A running (or finished) installation as seen by the UI.
+#[derive(Default)]
+pub struct InstallRun {
+    rx: Option<Receiver<Progress>>,
+    pub log: Vec<String>,
+    pub error: Option<String>,
+    pub cleanup_failures: Vec<String>,
+    checkpoint: Option<Checkpoint>,
+    abort: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl InstallRun {
+    /// Runs `steps` in the background, skipping `completed` and recording
+    /// progress in `checkpoint`.
+    pub fn start(&mut self, steps: Vec<Step>, completed: Vec<String>, checkpoint: Checkpoint) {
+        self.checkpoint = Some(checkpoint);
+        let (tx, rx) = mpsc::channel();
+        let abort = Arc::clone(&self.abort);
+        self.worker = Some(thread::spawn(move || install::run(&steps, &completed, &abort, &tx)));
+        self.rx = Some(rx);
+    }
+
+    /// True until the installation thread has finished, cleanup included.
+    pub fn is_running(&self) -> bool {
+        self.worker.as_ref().is_some_and(|worker| !worker.is_finished())
+    }
+
+    /// Stops the installation before its next step.
+    pub fn abort(&mut self) {
+        self.abort.store(true, Ordering::SeqCst);
+        self.log.push("Aborting after the current step...".to_string());
+    }
+
+    /// Collects progress; true once every step is done.
+    fn poll(&mut self) -> bool {
+        let Some(rx) = &self.rx else {
+            return false;
+        };
+        let mut done = false;
+        while let Ok(progress) = rx.try_recv() {
+            match progress {
+                Progress::StepStarted(i, name) => self.log.push(format!("==> [{}] {}", i + 1, name)),
+                Progress::Output(line) => self.log.push(line),
+                Progress::Failed(err) => self.error = Some(err),
+                Progress::CleanupFailed(failed) => self.cleanup_failures = failed,
+                Progress::Aborted(step) => self.error = Some(format!("aborted before {}", step)),
+                Progress::StepDone(id) => {
+                    if let Some(Err(err)) = self.checkpoint.as_mut().map(|c| c.mark_done(&id)) {
+                        self.log.push(format!("warning: could not save resume state: {}", err));
+                    }
+                }
+                Progress::Done => {
+                    checkpoint::clear();
+                    self.checkpoint = None;
+                    done = true;
+                }
+            }
+        }
+        done
+    }
+}
+
+pub struct Installing;
+
+impl PageView for Installing {
+    fn page(&self) -> Page {
+        Page::Installing
+    }
+
+    fn tick(&mut self, app: &mut AppState, ctx: &mut Context) {
+        if ctx.install.poll() {
+            app.page = Page::Finished;
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, ctx: &Context) {
+        let run = &ctx.install;
+        let height = area.height.saturating_sub(2) as usize;
+        let height = height.saturating_sub(run.cleanup_failures.len() + usize::from(!run.cleanup_failures.is_empty()));
+        let start = run.log.len().saturating_sub(height);
+        let mut lines: Vec<Line> = run.log[start..].iter().map(|l| Line::from(l.as_str())).collect();
+        if !run.cleanup_failures.is_empty() {
+            let red = Style::default().fg(Color::Red);
+            lines.push(Line::styled("Could not clean up (release these by hand):", red));
+            lines.extend(run.cleanup_failures.iter().map(|f| Line::styled(format!("  {}", f), red)));
+        }
+        let title = match &run.error {
+            Some(err) => format!("Installation failed: {} (Esc to quit, relaunch to resume, b for a support bundle)", err),
+            None => "Installing".to_string(),
+        };
+        let para = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(para, area);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, ctx: &mut Context) -> Flow {
+        let failed = ctx.install.error.is_some();
+        match key.code {
+            KeyCode::Esc if failed => Flow::Quit,
+            KeyCode::Esc | KeyCode::Char('q') if !failed => Flow::ConfirmQuit,
+            KeyCode::Char('b') if failed => {
+                ctx.install.log.push(match support::bundle(app, Path::new(".")) {
+                    Ok(path) => format!("Saved support bundle {}", path.display()),
+                    Err(err) => format!("Could not save support bundle: {}", err),
+                });
+                Flow::Stay
+            }
+            _ => Flow::Stay,
+        }
+    }
+}
+
+pub struct Finished;
+
+impl PageView for Finished {
+    fn page(&self) -> Page {
+        Page::Finished
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        let text = vec![Line::from("Installation finished! Please reboot your system.")];
+        let para = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Finished"));
+        f.render_widget(para, area);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, _app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => Flow::Quit,
+            _ => Flow::Stay,
+        }
+    }
+}
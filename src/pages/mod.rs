@@ -0,0 +1,318 @@
+// The wizard pages. Each page renders itself, handles its keys and says
+// whether it applies to the current answers; the registry order is the
+// order of the wizard.
+
+mod desktop;
+mod disk;
+mod install;
+mod network;
+mod packages;
+mod preflight;
+mod start;
+mod summary;
+mod system;
+mod text;
+mod wifi;
+
+pub use install::InstallRun;
+
+use crate::app::{AppState, Page};
+use crate::hardware;
+use crate::repo::SyncDb;
+use crossterm::event::KeyEvent;
+use ratatui::layout::Rect;
+use ratatui::Frame;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// What the wizard does after a page handled a key.
+pub enum Flow {
+    Stay,
+    /// Validate the page and move to the next applicable one.
+    Next,
+    Back,
+    /// Open the quit dialog.
+    ConfirmQuit,
+    Quit,
+}
+
+/// State shared between pages.
+#[derive(Default)]
+pub struct Context {
+    /// Synced package databases, loaded when a page first needs them.
+    pub sync_db: Option<SyncDb>,
+    sync_db_rx: Option<Receiver<SyncDb>>,
+    pub install: InstallRun,
+}
+
+impl Context {
+    /// Reading the databases runs bsdtar over every repository, so it happens
+    /// on a background thread; `sync_db` is set on a later call once it is done.
+    pub fn load_sync_db(&mut self) {
+        if self.sync_db.is_some() {
+            return;
+        }
+        match &self.sync_db_rx {
+            Some(rx) => match rx.try_recv() {
+                Ok(db) => self.sync_db = Some(db),
+                Err(TryRecvError::Empty) => {}
+                // The loader died; carry on as if nothing was synced.
+                Err(TryRecvError::Disconnected) => self.sync_db = Some(SyncDb::default()),
+            },
+            None => {
+                let (tx, rx) = mpsc::channel();
+                thread::spawn(move || {
+                    let _ = tx.send(SyncDb::load());
+                });
+                self.sync_db_rx = Some(rx);
+            }
+        }
+        if self.sync_db.is_some() {
+            self.sync_db_rx = None;
+        }
+    }
+
+    pub fn loading_sync_db(&self) -> bool {
+        self.sync_db_rx.is_some()
+    }
+}
+
+pub trait PageView {
+    fn page(&self) -> Page;
+
+    /// Pages that do not apply are skipped by Next and Back.
+    fn is_applicable(&self, _app: &AppState) -> bool {
+        true
+    }
+
+    /// Checked before leaving the page forwards.
+    fn validate(&self, _app: &AppState) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, ctx: &Context);
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, ctx: &mut Context) -> Flow;
+
+    /// Called when the wizard moves onto the page, so lists and inputs start
+    /// from the current answer rather than from wherever they were left.
+    fn enter(&mut self, _app: &AppState) {}
+
+    /// Text pasted into the terminal, for pages with a text input.
+    fn paste(&mut self, _text: &str) {}
+
+    /// Called on every loop iteration, on every page, to collect background work.
+    fn tick(&mut self, _app: &mut AppState, _ctx: &mut Context) {}
+}
+
+pub struct Registry {
+    pages: Vec<Box<dyn PageView>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let pages: Vec<Box<dyn PageView>> = vec![
+            Box::new(start::Resume::default()),
+            Box::new(start::Welcome),
+            Box::new(wifi::Wifi::default()),
+            Box::new(start::ProfilePage::default()),
+            Box::new(disk::YesNo::new(
+                Page::GrubInstall,
+                "Install GRUB bootloader?",
+                |app| app.install_grub,
+                |app, val| app.install_grub = Some(val),
+            )),
+            Box::new(
+                text::TextPage::new(
+                    Page::EfiPartition,
+                    "EFI Partition (e.g. /dev/sda1)",
+                    |app| app.efi_partition.as_deref(),
+                    |app, val| app.efi_partition = Some(val),
+                )
+                .when(is_uefi)
+                .required(),
+            ),
+            Box::new(
+                text::TextPage::new(
+                    Page::RootPartition,
+                    "Root Partition (e.g. /dev/sda2)",
+                    |app| app.root_partition.as_deref(),
+                    |app, val| app.root_partition = Some(val),
+                )
+                .required(),
+            ),
+            Box::new(disk::YesNo::new(
+                Page::RootFormat,
+                "Format root partition?",
+                |app| app.format_root,
+                |app, val| app.format_root = Some(val),
+            )),
+            Box::new(disk::Filesystem::default()),
+            Box::new(
+                text::TextPage::new(
+                    Page::Locale,
+                    "Locale (e.g. en_US.UTF-8)",
+                    |app| app.locale.as_deref(),
+                    |app, val| app.locale = Some(val),
+                )
+                .required(),
+            ),
+            Box::new(
+                text::TextPage::new(Page::Hostname, "Hostname", |app| app.hostname.as_deref(), |app, val| app.hostname = Some(val))
+                    .required(),
+            ),
+            Box::new(network::Network::default()),
+            Box::new(
+                text::TextPage::new(Page::Username, "Username", |app| app.username.as_deref(), |app, val| app.username = Some(val))
+                    .required(),
+            ),
+            Box::new(system::PasswordPage::new(Page::UserPassword, "User Password")),
+            Box::new(system::PasswordPage::new(Page::RootPassword, "Root Password")),
+            Box::new(system::Kernel::default()),
+            Box::new(system::Gpu::default()),
+            Box::new(desktop::Desktop::default()),
+            Box::new(desktop::Extras::default()),
+            Box::new(desktop::DisplayManagerPage::default()),
+            Box::new(desktop::Autologin::default()),
+            Box::new(packages::PacmanConf::default()),
+            Box::new(packages::ExtraPackages::default()),
+            Box::new(packages::Aur::default()),
+            Box::new(packages::Mirrors::default()),
+            Box::new(text::TextPage::new(
+                Page::OfflineRepo,
+                "Offline repository (directory or file:// URL, empty to install online)",
+                |app| app.offline_repo.as_deref(),
+                |app, val| app.offline_repo = Some(val).filter(|r| !r.is_empty()),
+            )),
+            // After the filesystem, network and mirror answers it checks.
+            Box::new(preflight::Preflight::default()),
+            Box::new(summary::Summary::default()),
+            Box::new(install::Installing),
+            Box::new(install::Finished),
+        ];
+        Registry { pages }
+    }
+}
+
+impl Registry {
+    fn position(&self, page: Page) -> usize {
+        self.pages.iter().position(|p| p.page() == page).expect("every page is registered")
+    }
+
+    pub fn get(&self, page: Page) -> &dyn PageView {
+        self.pages[self.position(page)].as_ref()
+    }
+
+    pub fn get_mut(&mut self, page: Page) -> &mut dyn PageView {
+        let pos = self.position(page);
+        self.pages[pos].as_mut()
+    }
+
+    pub fn enter(&mut self, app: &AppState) {
+        self.get_mut(app.page).enter(app);
+    }
+
+    pub fn tick(&mut self, app: &mut AppState, ctx: &mut Context) {
+        for page in &mut self.pages {
+            page.tick(app, ctx);
+        }
+    }
+
+    /// Moves to the next applicable page, or back to the summary after an edit.
+    pub fn next(&self, app: &mut AppState) {
+        if app.back_to_summary() {
+            return;
+        }
+        let pos = self.position(app.page);
+        if let Some(next) = self.pages[pos + 1..].iter().find(|p| p.is_applicable(app)) {
+            app.page = next.page();
+        }
+    }
+
+    pub fn prev(&self, app: &mut AppState) {
+        if app.back_to_summary() {
+            return;
+        }
+        let pos = self.position(app.page);
+        if let Some(prev) = self.pages[..pos].iter().rev().find(|p| p.is_applicable(app)) {
+            app.page = prev.page();
+        }
+    }
+}
+
+/// The EFI system partition is only asked for when booted in UEFI mode.
+fn is_uefi(_app: &AppState) -> bool {
+    hardware::is_uefi()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{DesktopEnv, KernelSelection};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn press(registry: &mut Registry, app: &mut AppState, code: KeyCode) {
+        let mut ctx = Context::default();
+        registry.get_mut(app.page).handle_key(KeyEvent::new(code, KeyModifiers::NONE), app, &mut ctx);
+    }
+
+    #[test]
+    fn next_and_prev_skip_pages_that_do_not_apply() {
+        let registry = Registry::default();
+        let mut app = AppState { page: Page::DesktopEnv, desktop_env: Some(DesktopEnv::None), ..AppState::default() };
+        registry.next(&mut app);
+        assert_eq!(app.page, Page::DisplayManager);
+        registry.prev(&mut app);
+        assert_eq!(app.page, Page::DesktopEnv);
+
+        app.desktop_env = Some(DesktopEnv::KDE);
+        registry.next(&mut app);
+        assert_eq!(app.page, Page::DesktopExtras);
+
+        // Without a saved installation there is nothing before the welcome page.
+        app.page = Page::Welcome;
+        registry.prev(&mut app);
+        assert_eq!(app.page, Page::Welcome);
+    }
+
+    #[test]
+    fn an_edit_from_the_summary_returns_there() {
+        let registry = Registry::default();
+        let mut app = AppState::default();
+        app.edit_from_summary(Page::Hostname);
+        registry.next(&mut app);
+        assert_eq!(app.page, Page::Summary);
+        assert!(!app.return_to_summary);
+
+        app.edit_from_summary(Page::Kernel);
+        registry.prev(&mut app);
+        assert_eq!(app.page, Page::Summary);
+
+        // Once back, the wizard moves through the pages as usual again.
+        app.page = Page::Hostname;
+        registry.next(&mut app);
+        assert_eq!(app.page, Page::Network);
+    }
+
+    #[test]
+    fn entering_a_page_starts_from_the_stored_answer() {
+        let mut registry = Registry::default();
+        let mut app = AppState { page: Page::Kernel, kernel: Some(KernelSelection::Both), ..AppState::default() };
+        registry.enter(&app);
+        press(&mut registry, &mut app, KeyCode::Home);
+        // Changed elsewhere, e.g. by loading saved answers, before coming back.
+        app.kernel = Some(KernelSelection::LinuxLTS);
+        registry.enter(&app);
+        press(&mut registry, &mut app, KeyCode::Enter);
+        assert_eq!(app.kernel, Some(KernelSelection::LinuxLTS));
+
+        app.page = Page::Hostname;
+        registry.enter(&app);
+        press(&mut registry, &mut app, KeyCode::Char('x'));
+        app.hostname = Some("workstation".to_string());
+        registry.enter(&app);
+        press(&mut registry, &mut app, KeyCode::Char('2'));
+        press(&mut registry, &mut app, KeyCode::Enter);
+        assert_eq!(app.hostname.as_deref(), Some("workstation2"));
+    }
+}
@@ -0,0 +1,193 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, NetworkStack, Page};
+use crate::network::{self, StaticAddress};
+use crate::widgets::{CheckboxList, ListEvent, SelectList, TextInput};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+use std::path::Path;
+
+const COPY_WIFI: &str = "Copy Wi-Fi credentials from the live system";
+
+/// Which part of the page takes keys; Tab cycles through them.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Stack,
+    CopyWifi,
+    Address,
+}
+
+pub struct Network {
+    focus: Focus,
+    stack: SelectList<NetworkStack>,
+    copy_wifi: CheckboxList<&'static str>,
+    input: TextInput,
+    error: Option<String>,
+    /// Networks saved by iwd on the live system, counted on entering.
+    saved_wifi: usize,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Network {
+            focus: Focus::Stack,
+            stack: SelectList::new(NetworkStack::ALL.to_vec()),
+            copy_wifi: CheckboxList::new(vec![COPY_WIFI]),
+            input: TextInput::default(),
+            error: None,
+            saved_wifi: 0,
+        }
+    }
+}
+
+impl Network {
+    fn leave(&mut self, flow: Flow) -> Flow {
+        self.input.clear();
+        self.error = None;
+        flow
+    }
+
+    fn add_address(&mut self, app: &mut AppState) {
+        let known = |name: &str| app.detected_interfaces.is_empty() || app.detected_interfaces.iter().any(|i| i.name == name);
+        match StaticAddress::parse(self.input.value()) {
+            Ok(addr) if !known(&addr.interface) => self.error = Some(format!("no interface named `{}`", addr.interface)),
+            Ok(addr) => {
+                app.static_addresses.push(addr);
+                self.input.clear();
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+}
+
+impl PageView for Network {
+    fn page(&self) -> Page {
+        Page::Network
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.stack.select(&app.network_stack.unwrap_or(NetworkStack::NetworkManager));
+        self.focus = Focus::Stack;
+        self.saved_wifi = network::load_wifi_networks(Path::new(network::IWD_DIR)).len();
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let page_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Min(3), Constraint::Length(3), Constraint::Length(3)].as_ref())
+            .split(area);
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+            .split(page_chunks[0]);
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+            .split(page_chunks[1]);
+
+        self.stack.render(f, top[0], if self.focus == Focus::Stack { "* Network stack" } else { "Network stack" });
+        let title = format!("{}Wi-Fi ({} saved)", if self.focus == Focus::CopyWifi { "* " } else { "" }, self.saved_wifi);
+        self.copy_wifi.render(f, top[1], &title, |_| app.copy_wifi == Some(true));
+
+        let interfaces: Vec<ListItem> = app
+            .detected_interfaces
+            .iter()
+            .map(|i| {
+                let kind = if i.wireless { "wireless" } else { "wired" };
+                ListItem::new(format!("{} ({}) {}", i.name, kind, i.mac.as_deref().unwrap_or("")))
+            })
+            .collect();
+        let interfaces = List::new(interfaces).block(Block::default().borders(Borders::ALL).title("Interfaces"));
+        f.render_widget(interfaces, body[0]);
+
+        let addresses: Vec<ListItem> = app
+            .static_addresses
+            .iter()
+            .map(|a| {
+                ListItem::new(format!(
+                    "{} {} via {} dns {}",
+                    a.interface,
+                    a.address,
+                    a.gateway.as_deref().unwrap_or("-"),
+                    a.dns.join(" ")
+                ))
+            })
+            .collect();
+        let addresses = List::new(addresses)
+            .block(Block::default().borders(Borders::ALL).title("Static addresses, others use DHCP (Del removes last)"));
+        f.render_widget(addresses, body[1]);
+
+        self.input.render(f, page_chunks[2], "Add static address", self.focus == Focus::Address);
+        let help = match &self.error {
+            Some(err) => Line::styled(err.as_str(), Style::default().fg(Color::Red)),
+            None => Line::from("Tab: next field  Space: toggle  Type `interface address/prefix [gateway|-] [dns...]`, Enter adds it"),
+        };
+        f.render_widget(Paragraph::new(help).block(Block::default().borders(Borders::ALL)), page_chunks[3]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match key.code {
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Stack => Focus::CopyWifi,
+                    Focus::CopyWifi => Focus::Address,
+                    Focus::Address => Focus::Stack,
+                };
+                return Flow::Stay;
+            }
+            KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Stack => Focus::Address,
+                    Focus::CopyWifi => Focus::Stack,
+                    Focus::Address => Focus::CopyWifi,
+                };
+                return Flow::Stay;
+            }
+            KeyCode::Esc => return self.leave(Flow::Back),
+            KeyCode::Delete if self.focus != Focus::Address => {
+                app.static_addresses.pop();
+                return Flow::Stay;
+            }
+            _ => {}
+        }
+        match self.focus {
+            Focus::Stack => match self.stack.handle_key(key.code) {
+                ListEvent::Moved => app.network_stack = self.stack.selected().copied(),
+                ListEvent::Chosen(stack) => {
+                    app.network_stack = Some(stack);
+                    return self.leave(Flow::Next);
+                }
+                ListEvent::Cancelled => return self.leave(Flow::Back),
+                _ => {}
+            },
+            Focus::CopyWifi => match self.copy_wifi.handle_key(key.code) {
+                ListEvent::Toggled(_) => app.copy_wifi = Some(app.copy_wifi != Some(true)),
+                ListEvent::Chosen(_) => return self.leave(Flow::Next),
+                ListEvent::Cancelled => return self.leave(Flow::Back),
+                _ => {}
+            },
+            Focus::Address => match key.code {
+                KeyCode::Enter if self.input.value().trim().is_empty() => return self.leave(Flow::Next),
+                KeyCode::Enter => self.add_address(app),
+                _ if self.input.handle_key(key) => {}
+                KeyCode::Delete => {
+                    app.static_addresses.pop();
+                }
+                _ => {}
+            },
+        }
+        Flow::Stay
+    }
+
+    fn paste(&mut self, text: &str) {
+        if self.focus == Focus::Address {
+            self.input.paste(text);
+        }
+    }
+}
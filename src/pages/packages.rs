@@ -0,0 +1,450 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, AurHelper, Page};
+use crate::mirrors::{self, CurlClient, Mirror};
+use crate::pacman_conf::{CustomRepo, DEFAULT_PARALLEL_DOWNLOADS, PARALLEL_DOWNLOADS};
+use crate::repo::RepoPackage;
+use crate::widgets::{CheckboxList, Choice, ListEvent, SelectList, TextInput};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// The pacman.conf switches offered as checkboxes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PacmanOption {
+    Multilib,
+    Color,
+    Candy,
+}
+
+impl PacmanOption {
+    const ALL: [PacmanOption; 3] = [PacmanOption::Multilib, PacmanOption::Color, PacmanOption::Candy];
+
+    fn flag(self, app: &mut AppState) -> &mut Option<bool> {
+        match self {
+            PacmanOption::Multilib => &mut app.multilib,
+            PacmanOption::Color => &mut app.pacman_color,
+            PacmanOption::Candy => &mut app.i_love_candy,
+        }
+    }
+
+    fn is_set(self, app: &AppState) -> bool {
+        let flag = match self {
+            PacmanOption::Multilib => app.multilib,
+            PacmanOption::Color => app.pacman_color,
+            PacmanOption::Candy => app.i_love_candy,
+        };
+        flag == Some(true)
+    }
+}
+
+impl Choice for PacmanOption {
+    fn label(&self) -> String {
+        match self {
+            PacmanOption::Multilib => "Enable multilib (32-bit libraries for Steam and Wine)",
+            PacmanOption::Color => "Colored output",
+            PacmanOption::Candy => "ILoveCandy progress bar",
+        }
+        .to_string()
+    }
+}
+
+pub struct PacmanConf {
+    options: CheckboxList<PacmanOption>,
+    input: TextInput,
+    /// Whether keys go to the repository input rather than the options.
+    editing_repo: bool,
+    error: Option<String>,
+}
+
+impl Default for PacmanConf {
+    fn default() -> Self {
+        PacmanConf {
+            options: CheckboxList::new(PacmanOption::ALL.to_vec()),
+            input: TextInput::default(),
+            editing_repo: false,
+            error: None,
+        }
+    }
+}
+
+impl PacmanConf {
+    fn leave(&mut self, flow: Flow) -> Flow {
+        self.input.clear();
+        self.error = None;
+        flow
+    }
+
+    fn add_repo(&mut self, app: &mut AppState) {
+        match CustomRepo::parse(self.input.value()) {
+            Ok(repo) => {
+                app.custom_repos.retain(|r| r.name != repo.name);
+                app.custom_repos.push(repo);
+                self.input.clear();
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+}
+
+impl PageView for PacmanConf {
+    fn page(&self) -> Page {
+        Page::PacmanConf
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let page_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Length(3), Constraint::Min(3), Constraint::Length(3)].as_ref())
+            .split(area);
+        let title = format!("pacman.conf, < {} > parallel downloads", app.parallel_downloads.unwrap_or(DEFAULT_PARALLEL_DOWNLOADS));
+        self.options.render(f, page_chunks[0], &title, |option| option.is_set(app));
+        self.input.render(f, page_chunks[1], "Add repository", self.editing_repo);
+
+        let repos: Vec<ListItem> = app
+            .custom_repos
+            .iter()
+            .map(|r| ListItem::new(format!("[{}] {} key: {}", r.name, r.server, r.key_id.as_deref().unwrap_or("none"))))
+            .collect();
+        let repos = List::new(repos).block(Block::default().borders(Borders::ALL).title("Custom repositories (Del removes last)"));
+        f.render_widget(repos, page_chunks[2]);
+
+        let help = match &self.error {
+            Some(err) => Line::styled(err.as_str(), Style::default().fg(Color::Red)),
+            None => Line::from("Space: toggle  Left/Right: downloads  Tab: type `name server [key-id]`, Enter adds it"),
+        };
+        f.render_widget(Paragraph::new(help).block(Block::default().borders(Borders::ALL)), page_chunks[3]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match key.code {
+            KeyCode::Tab | KeyCode::BackTab => self.editing_repo = !self.editing_repo,
+            KeyCode::Esc => return self.leave(Flow::Back),
+            KeyCode::Enter if self.editing_repo && !self.input.value().trim().is_empty() => self.add_repo(app),
+            KeyCode::Enter => return self.leave(Flow::Next),
+            _ if self.editing_repo && self.input.handle_key(key) => {}
+            KeyCode::Delete => {
+                app.custom_repos.pop();
+            }
+            KeyCode::Left | KeyCode::Right if !self.editing_repo => {
+                let n = app.parallel_downloads.unwrap_or(DEFAULT_PARALLEL_DOWNLOADS);
+                let n = if key.code == KeyCode::Right { n + 1 } else { n.saturating_sub(1) };
+                app.parallel_downloads = Some(n.clamp(*PARALLEL_DOWNLOADS.start(), *PARALLEL_DOWNLOADS.end()));
+            }
+            _ if self.editing_repo => {}
+            _ => match self.options.handle_key(key.code) {
+                ListEvent::Toggled(option) => {
+                    let flag = option.flag(app);
+                    *flag = Some(*flag != Some(true));
+                }
+                ListEvent::Cancelled => return self.leave(Flow::Back),
+                _ => {}
+            },
+        }
+        Flow::Stay
+    }
+
+    fn paste(&mut self, text: &str) {
+        if self.editing_repo {
+            self.input.paste(text);
+        }
+    }
+}
+
+impl Choice for RepoPackage {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> Option<String> {
+        Some(format!("({}/{}) - {}", self.repo, self.version, self.description))
+    }
+}
+
+#[derive(Default)]
+pub struct ExtraPackages {
+    input: TextInput,
+    results: CheckboxList<RepoPackage>,
+    /// The query `results` were searched for.
+    searched: Option<String>,
+}
+
+impl ExtraPackages {
+    fn refresh(&mut self, ctx: &Context) {
+        let Some(db) = ctx.sync_db.as_ref() else {
+            return;
+        };
+        if self.searched.as_deref() != Some(self.input.value()) {
+            self.results.set_items(db.search(self.input.value()).into_iter().cloned().collect());
+            self.searched = Some(self.input.value().to_string());
+        }
+    }
+}
+
+impl PageView for ExtraPackages {
+    fn page(&self) -> Page {
+        Page::ExtraPackages
+    }
+
+    fn tick(&mut self, app: &mut AppState, ctx: &mut Context) {
+        if app.page == Page::ExtraPackages {
+            ctx.load_sync_db();
+            self.refresh(ctx);
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, ctx: &Context) {
+        let db = ctx.sync_db.as_ref();
+        let page_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Length(3)].as_ref())
+            .split(area);
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+            .split(page_chunks[1]);
+
+        self.input.render(f, page_chunks[0], "Search packages", true);
+        self.results
+            .render(f, body[0], "Results (Space or Enter to mark)", |p| app.extra_packages.contains(&p.name));
+
+        let searchable = db.is_some_and(|db| !db.is_empty());
+        let selected: Vec<ListItem> = app
+            .extra_packages
+            .iter()
+            .map(|name| {
+                if !searchable || db.is_some_and(|db| db.contains(name)) {
+                    ListItem::new(name.as_str())
+                } else {
+                    ListItem::new(format!("{} (not found)", name)).style(Style::default().fg(Color::Red))
+                }
+            })
+            .collect();
+        let selected = List::new(selected).block(Block::default().borders(Borders::ALL).title("Marked (Del removes last)"));
+        f.render_widget(selected, body[1]);
+
+        let help = if searchable {
+            format!("{} packages indexed. Enter on an empty search continues.", db.map_or(0, |db| db.len()))
+        } else if ctx.loading_sync_db() {
+            "Reading the synced package databases...".to_string()
+        } else {
+            "No synced databases in /var/lib/pacman/sync (run pacman -Sy); names are added as typed.".to_string()
+        };
+        f.render_widget(Paragraph::new(help).block(Block::default().borders(Borders::ALL)), page_chunks[2]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, ctx: &mut Context) -> Flow {
+        match key.code {
+            KeyCode::Esc => {
+                self.input.clear();
+                return Flow::Back;
+            }
+            KeyCode::Enter if self.input.value().trim().is_empty() => return Flow::Next,
+            KeyCode::Up | KeyCode::Down | KeyCode::Char(' ') | KeyCode::Enter => match self.results.handle_key(key.code) {
+                ListEvent::Toggled(package) | ListEvent::Chosen(package) => app.toggle_extra_package(&package.name),
+                ListEvent::Ignored if key.code == KeyCode::Enter => app.toggle_extra_package(self.input.value().trim()),
+                _ => {}
+            },
+            _ if self.input.handle_key(key) => self.refresh(ctx),
+            KeyCode::Delete => {
+                app.extra_packages.pop();
+            }
+            _ => {}
+        }
+        Flow::Stay
+    }
+
+    fn paste(&mut self, text: &str) {
+        self.input.paste(text);
+    }
+}
+
+pub struct Aur {
+    helper: SelectList<AurHelper>,
+    input: TextInput,
+}
+
+impl Default for Aur {
+    fn default() -> Self {
+        Aur { helper: SelectList::new(AurHelper::ALL.to_vec()), input: TextInput::default() }
+    }
+}
+
+impl PageView for Aur {
+    fn page(&self) -> Page {
+        Page::Aur
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.helper.select(&app.aur_helper.unwrap_or(AurHelper::None));
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let page_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Length(3), Constraint::Min(3)].as_ref())
+            .split(area);
+        self.helper.render(f, page_chunks[0], "AUR helper (built as the created user)");
+        self.input.render(f, page_chunks[1], "AUR packages (Enter adds, Enter on empty continues)", true);
+        let note = if app.offline_repo.is_some() {
+            "Skipped for offline installs: AUR builds need network access."
+        } else {
+            "Failures here are logged and do not stop the installation."
+        };
+        let lines = vec![
+            Line::from(format!("Packages: {}", app.aur_packages.join(" "))),
+            Line::from("Del removes the last package."),
+            Line::from(note),
+        ];
+        f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL)), page_chunks[2]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match key.code {
+            KeyCode::Up | KeyCode::Down => {
+                self.helper.handle_key(key.code);
+            }
+            KeyCode::Enter if self.input.value().trim().is_empty() => {
+                app.aur_helper = self.helper.selected().copied();
+                return Flow::Next;
+            }
+            KeyCode::Enter => {
+                for name in self.input.take().split_whitespace() {
+                    if !app.aur_packages.iter().any(|p| p == name) {
+                        app.aur_packages.push(name.to_string());
+                    }
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                return Flow::Back;
+            }
+            _ if self.input.handle_key(key) => {}
+            KeyCode::Delete => {
+                app.aur_packages.pop();
+            }
+            _ => {}
+        }
+        Flow::Stay
+    }
+
+    fn paste(&mut self, text: &str) {
+        self.input.paste(text);
+    }
+}
+
+impl Choice for Mirror {
+    fn label(&self) -> String {
+        let speed = self.speed.map_or(String::from("      -"), |s| format!("{:>7.1}", s / 1024.0 / 1024.0));
+        format!("{} MiB/s  {:<16} {}", speed, self.country.as_deref().unwrap_or("-"), self.url)
+    }
+}
+
+#[derive(Default)]
+pub struct Mirrors {
+    list: SelectList<Mirror>,
+    rank_rx: Option<Receiver<Vec<Mirror>>>,
+}
+
+impl Mirrors {
+    /// Rebuilds the list from the current filter, with the cursor on top.
+    fn reset(&mut self, app: &AppState) {
+        self.list = SelectList::new(app.filtered_mirrors().into_iter().cloned().collect());
+    }
+}
+
+impl PageView for Mirrors {
+    fn page(&self) -> Page {
+        Page::Mirrors
+    }
+
+    fn tick(&mut self, app: &mut AppState, _ctx: &mut Context) {
+        if let Some(ranked) = self.rank_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            for mirror in ranked.iter().rev() {
+                if let Some(pos) = app.available_mirrors.iter().position(|m| m.url == mirror.url) {
+                    app.available_mirrors.remove(pos);
+                }
+                app.available_mirrors.insert(0, mirror.clone());
+            }
+            self.rank_rx = None;
+            self.reset(app);
+        }
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.reset(app);
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let page_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(4)].as_ref())
+            .split(area);
+        let title = format!("Mirrors ({} match)", app.filtered_mirrors().len());
+        self.list.render(f, page_chunks[0], &title);
+        let status = if self.rank_rx.is_some() {
+            format!("Measuring download speed of up to {} mirrors...", mirrors::RANK_LIMIT)
+        } else {
+            "Left/Right: country  Tab: protocol  r: rank by speed  Enter: use these mirrors".to_string()
+        };
+        let lines = vec![
+            Line::from(format!(
+                "Country: {}   Protocol: {}",
+                app.mirror_country.as_deref().unwrap_or("All"),
+                app.mirror_protocol.as_deref().unwrap_or("Any")
+            )),
+            Line::from(status),
+        ];
+        f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL)), page_chunks[1]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match key.code {
+            KeyCode::Up | KeyCode::Down | KeyCode::Home | KeyCode::End => {
+                self.list.handle_key(key.code);
+            }
+            KeyCode::Left | KeyCode::Right => {
+                let countries = mirrors::countries(&app.available_mirrors);
+                let pos = app
+                    .mirror_country
+                    .as_ref()
+                    .and_then(|c| countries.iter().position(|x| x == c))
+                    .map_or(0, |i| i + 1);
+                let len = countries.len() + 1;
+                let pos = if key.code == KeyCode::Right { (pos + 1) % len } else { (pos + len - 1) % len };
+                app.mirror_country = if pos == 0 { None } else { Some(countries[pos - 1].clone()) };
+                self.reset(app);
+            }
+            KeyCode::Tab => {
+                app.mirror_protocol = match app.mirror_protocol.as_deref() {
+                    None => Some("https".to_string()),
+                    Some("https") => Some("http".to_string()),
+                    _ => None,
+                };
+                self.reset(app);
+            }
+            KeyCode::Char('r') if self.rank_rx.is_none() => {
+                let candidates: Vec<Mirror> = app.filtered_mirrors().into_iter().cloned().collect();
+                let (tx, rx) = mpsc::channel();
+                thread::spawn(move || {
+                    let _ = tx.send(mirrors::rank(candidates, &CurlClient));
+                });
+                self.rank_rx = Some(rx);
+            }
+            KeyCode::Enter => {
+                app.mirrorlist = app.filtered_mirrors().iter().map(|m| m.url.clone()).collect();
+                return Flow::Next;
+            }
+            KeyCode::Esc | KeyCode::Backspace => return Flow::Back,
+            _ => {}
+        }
+        Flow::Stay
+    }
+}
@@ -0,0 +1,115 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, Page};
+use crate::mirrors::{CurlClient, Mirror};
+use crate::preflight::{self, Check, Status};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+#[derive(Default)]
+pub struct Preflight {
+    checks: Option<Vec<Check>>,
+    rx: Option<Receiver<Vec<Check>>>,
+}
+
+impl PageView for Preflight {
+    fn page(&self) -> Page {
+        Page::Preflight
+    }
+
+    /// Runs the checks again on every visit, so they match the current
+    /// filesystem, connection and mirrors.
+    fn enter(&mut self, _app: &AppState) {
+        self.checks = None;
+        self.rx = None;
+    }
+
+    fn tick(&mut self, app: &mut AppState, _ctx: &mut Context) {
+        if let Some(checks) = self.rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.checks = Some(checks);
+            self.rx = None;
+        }
+
+        if app.page == Page::Preflight && self.checks.is_none() && self.rx.is_none() {
+            let filesystem = app.filesystem.clone().unwrap_or_else(|| "ext4".to_string());
+            let mirror = match app.mirrorlist.first() {
+                Some(url) => Some(Mirror { url: url.clone(), country: None, speed: None }.test_url()),
+                None => app.available_mirrors.first().map(Mirror::test_url),
+            };
+            let (tx, rx) = mpsc::channel();
+            thread::spawn(move || {
+                let _ = tx.send(preflight::run(&filesystem, mirror.as_deref(), &CurlClient));
+            });
+            self.rx = Some(rx);
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        let mut lines = Vec::new();
+        match &self.checks {
+            None => lines.push(Line::from("Running checks...")),
+            Some(checks) => {
+                for check in checks {
+                    let color = match check.status {
+                        Status::Pass => Color::LightGreen,
+                        Status::Warn => Color::Yellow,
+                        Status::Fail => Color::Red,
+                    };
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("[{}] ", check.status.label()), Style::default().fg(color)),
+                        Span::raw(format!("{}: {}", check.name, check.detail)),
+                    ]));
+                    if let Some(hint) = &check.hint {
+                        lines.push(Line::from(format!("       {}", hint)));
+                    }
+                }
+                lines.push(Line::from(""));
+                if checks.iter().any(|c| c.status == Status::Fail) {
+                    lines.push(Line::from("Fix the failed checks and press r to run them again, or i to continue anyway."));
+                } else {
+                    lines.push(Line::from("Press Enter to continue, r to run the checks again."));
+                }
+            }
+        }
+        let para = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Preflight checks"));
+        f.render_widget(para, area);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, _app: &mut AppState, _ctx: &mut Context) -> Flow {
+        let failed = self.checks.as_ref().is_none_or(|checks| checks.iter().any(|c| c.status == Status::Fail));
+        match key.code {
+            KeyCode::Enter if !failed => Flow::Next,
+            KeyCode::Char('i') if self.checks.is_some() => Flow::Next,
+            KeyCode::Char('r') if self.rx.is_none() => {
+                self.checks = None;
+                Flow::Stay
+            }
+            KeyCode::Esc => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entering_runs_the_checks_again() {
+        let mut page = Preflight {
+            checks: Some(vec![Check { name: "Memory", status: Status::Pass, detail: "4096 MiB".into(), hint: None }]),
+            rx: None,
+        };
+        page.enter(&AppState::default());
+        assert!(page.checks.is_none());
+    }
+}
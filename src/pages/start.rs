@@ -0,0 +1,226 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, Page};
+use crate::checkpoint;
+use crate::install;
+use crate::logging::{self, Kind};
+use crate::profiles::Profile;
+use crate::widgets::{Choice, ListEvent, PasswordField, SelectList};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+#[derive(Default)]
+pub struct Resume {
+    root_password: PasswordField,
+    user_password: PasswordField,
+    /// 0 for the root password field, 1 for the user password field.
+    focus: usize,
+    error: Option<String>,
+    /// The progress line, worked out from the plan once per visit.
+    progress: Vec<String>,
+}
+
+impl Resume {
+    /// Passwords are never saved, so they are asked again until the user exists.
+    fn needs_passwords(app: &AppState) -> bool {
+        app.resume
+            .as_ref()
+            .is_some_and(|state| !state.is_done(&install::step_id(install::CREATE_USER_STEP)))
+    }
+
+    fn start_over(&mut self, app: &mut AppState) {
+        checkpoint::clear();
+        app.resume = None;
+        app.page = Page::Welcome;
+    }
+
+    fn focused(&mut self) -> &mut PasswordField {
+        if self.focus == 0 {
+            &mut self.root_password
+        } else {
+            &mut self.user_password
+        }
+    }
+}
+
+impl PageView for Resume {
+    fn page(&self) -> Page {
+        Page::Resume
+    }
+
+    fn is_applicable(&self, app: &AppState) -> bool {
+        app.resume.is_some()
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.root_password.clear();
+        self.user_password.clear();
+        self.focus = 0;
+        self.error = None;
+        self.progress.clear();
+        if let Some(state) = &app.resume {
+            let steps = install::plan(app);
+            let next = steps.iter().find(|s| s.rerun || !state.is_done(&s.id())).map_or("none", |s| s.name.as_str());
+            let done = steps.iter().filter(|s| state.is_done(&s.id())).count();
+            self.progress = vec![
+                format!("A previous installation stopped after {} of {} steps.", done, steps.len()),
+                format!("Resuming continues with: {}", next),
+            ];
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let mut text: Vec<Line> = self.progress.iter().map(|l| Line::from(l.as_str())).collect();
+        text.push(Line::from(""));
+        if Self::needs_passwords(app) {
+            text.push(Line::from("The user has not been created yet; enter the passwords again (Tab switches)."));
+        }
+        text.push(Line::from("Press Enter to resume, or n (Esc) to start over with the saved answers."));
+        if let Some(err) = &self.error {
+            text.push(Line::styled(err.as_str(), Style::default().fg(Color::Red)));
+        }
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(6), Constraint::Length(3), Constraint::Length(3)].as_ref())
+            .split(area);
+        let para = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Resume installation"));
+        if Self::needs_passwords(app) {
+            f.render_widget(para, chunks[0]);
+            self.root_password.render(f, chunks[1], "Root Password", self.focus == 0);
+            self.user_password.render(f, chunks[2], "User Password", self.focus == 1);
+        } else {
+            f.render_widget(para, area);
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, ctx: &mut Context) -> Flow {
+        let needs_passwords = Self::needs_passwords(app);
+        match key.code {
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down if needs_passwords => self.focus = 1 - self.focus,
+            KeyCode::Enter if needs_passwords && self.focus == 0 => self.focus = 1,
+            KeyCode::Enter => {
+                if needs_passwords {
+                    if self.root_password.is_empty() || self.user_password.is_empty() {
+                        self.error = Some("Both passwords are required.".to_string());
+                        return Flow::Stay;
+                    }
+                    app.root_password = Some(self.root_password.take());
+                    app.user_password = Some(self.user_password.take());
+                    logging::log(Kind::Decision, "passwords entered again to resume");
+                }
+                let state = app.resume.take().unwrap_or_default();
+                let steps = install::plan(app);
+                let completed = state.completed.clone();
+                ctx.install.start(steps, completed, state);
+                app.page = Page::Installing;
+            }
+            KeyCode::Char('n') if !needs_passwords => self.start_over(app),
+            KeyCode::Esc => self.start_over(app),
+            _ if needs_passwords => {
+                self.focused().handle_key(key);
+            }
+            _ => {}
+        }
+        Flow::Stay
+    }
+
+    fn paste(&mut self, text: &str) {
+        self.focused().paste(text);
+    }
+}
+
+pub struct Welcome;
+
+impl PageView for Welcome {
+    fn page(&self) -> Page {
+        Page::Welcome
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        let text = vec![
+            Line::from("Welcome to the Arch Linux Installer TUI!"),
+            Line::from("Press Enter to start."),
+        ];
+        let para = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Welcome"));
+        f.render_widget(para, area);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, _app: &mut AppState, _ctx: &mut Context) -> Flow {
+        if key.code == KeyCode::Enter {
+            return Flow::Next;
+        }
+        Flow::Stay
+    }
+}
+
+/// `None` answers every question by hand.
+impl Choice for Option<Profile> {
+    fn label(&self) -> String {
+        match self {
+            Some(profile) => profile.name.clone(),
+            None => "Custom".to_string(),
+        }
+    }
+
+    fn description(&self) -> Option<String> {
+        Some(match self {
+            Some(profile) => profile.description.clone(),
+            None => "answer every question".to_string(),
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct ProfilePage {
+    list: SelectList<Option<Profile>>,
+}
+
+impl PageView for ProfilePage {
+    fn page(&self) -> Page {
+        Page::Profile
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        let mut options = vec![None];
+        options.extend(app.available_profiles.iter().cloned().map(Some));
+        self.list.set_items(options);
+        let current = app.profile.as_ref().and_then(|name| app.available_profiles.iter().find(|p| &p.name == name));
+        self.list.select(&current.cloned());
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let page_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(6), Constraint::Length(app.profile_errors.len() as u16 + 2)].as_ref())
+            .split(area);
+        self.list.render(f, page_chunks[0], "Profile (later pages can still override)");
+        if !app.profile_errors.is_empty() {
+            let lines: Vec<Line> = app
+                .profile_errors
+                .iter()
+                .map(|e| Line::styled(e.as_str(), Style::default().fg(Color::Red)))
+                .collect();
+            f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Invalid profiles")), page_chunks[1]);
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(Some(profile)) => {
+                profile.apply(app);
+                Flow::Next
+            }
+            ListEvent::Chosen(None) => {
+                app.profile = None;
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
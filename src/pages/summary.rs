@@ -0,0 +1,211 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, Page};
+use crate::checkpoint::Checkpoint;
+use crate::config;
+use crate::install;
+use crate::packages;
+use crate::preflight;
+use crate::summary;
+use crate::support;
+use crate::widgets::{Choice, ListEvent, SelectList};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use std::path::Path;
+
+/// A line of the summary list: an answer, or the final "Start installation".
+#[derive(Clone)]
+enum Entry {
+    Answer(summary::Row),
+    Start,
+}
+
+impl Choice for Entry {
+    fn label(&self) -> String {
+        match self {
+            Entry::Answer(row) => row.label.to_string(),
+            Entry::Start => "Start installation".to_string(),
+        }
+    }
+
+    fn description(&self) -> Option<String> {
+        match self {
+            Entry::Answer(row) => Some(match row.problem {
+                Some(problem) => format!("{}  ({})", row.value, problem),
+                None => row.value.clone(),
+            }),
+            Entry::Start => None,
+        }
+    }
+
+    fn style(&self) -> Style {
+        match self {
+            Entry::Answer(row) if row.problem.is_some() => Style::default().fg(Color::Red),
+            Entry::Answer(_) => Style::default(),
+            Entry::Start => Style::default().fg(Color::Yellow),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Summary {
+    list: SelectList<Entry>,
+    note: Option<String>,
+    /// Worked out once per visit; the answers only change on other pages.
+    packages: packages::PackageSet,
+    missing_tools: Vec<String>,
+    /// Extra packages not in the synced databases, once those are loaded.
+    missing_packages: Option<Vec<String>>,
+}
+
+impl Summary {
+    /// The answers can change while the page is shown, e.g. the connection.
+    fn refresh(&mut self, app: &AppState) {
+        let mut entries: Vec<Entry> = summary::rows(app).into_iter().map(Entry::Answer).collect();
+        entries.push(Entry::Start);
+        self.list.set_items(entries);
+    }
+}
+
+impl PageView for Summary {
+    fn page(&self) -> Page {
+        Page::Summary
+    }
+
+    fn validate(&self, app: &AppState) -> Result<(), String> {
+        if summary::rows(app).iter().any(|r| r.problem.is_some()) {
+            return Err("Fix the answers marked in red first.".to_string());
+        }
+        Ok(())
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.packages = packages::resolve(app);
+        self.missing_tools = preflight::missing_tools(app.filesystem.as_deref().unwrap_or("ext4"));
+        self.missing_packages = None;
+        self.refresh(app);
+    }
+
+    fn tick(&mut self, app: &mut AppState, ctx: &mut Context) {
+        if app.page == Page::Summary {
+            ctx.load_sync_db();
+            if self.missing_packages.is_none() {
+                if let Some(db) = &ctx.sync_db {
+                    let searchable = !db.is_empty();
+                    self.missing_packages =
+                        Some(app.extra_packages.iter().filter(|p| searchable && !db.contains(p)).cloned().collect());
+                }
+            }
+            self.refresh(app);
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let mut lines = Vec::new();
+        lines.push(Line::from(match app.cpu_vendor {
+            Some(cpu) => format!("CPU microcode: {} (detected {} CPU)", cpu.microcode_package(), cpu.label()),
+            None => "CPU microcode: none (CPU vendor not detected)".to_string(),
+        }));
+        let packages = &self.packages;
+        lines.push(Line::from(format!("Packages: {} total", packages.total())));
+        lines.push(Line::from(format!("  pacstrap ({}): {}", packages.pacstrap.len(), packages.pacstrap.join(" "))));
+        lines.push(Line::from(format!("  chroot ({}): {}", packages.chroot.len(), packages.chroot.join(" "))));
+        lines.push(Line::from(format!("Extra services: {}", app.extra_services.join(" "))));
+        lines.push(Line::from(format!("User groups: wheel {}", app.user_groups.join(" "))));
+        if let Some(missing) = self.missing_packages.as_ref().filter(|missing| !missing.is_empty()) {
+            lines.push(Line::styled(
+                format!("Missing from synced repositories: {}", missing.join(" ")),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        if !self.missing_tools.is_empty() {
+            lines.push(Line::styled(
+                format!("Missing tools on the live system: {}", self.missing_tools.join(" ")),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        if let Some(note) = &self.note {
+            lines.push(Line::from(note.as_str()));
+        }
+        lines.push(Line::from(format!(
+            "Enter on a row changes it, on \"Start installation\" installs; s saves answers to {}, b saves a support bundle.",
+            config::DEFAULT_PATH
+        )));
+        let summary_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(6), Constraint::Length(lines.len() as u16 + 4)].as_ref())
+            .split(area);
+        self.list.render(f, summary_chunks[0], "Summary (Enter to change an answer)");
+        let para = Paragraph::new(lines).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title("Details"));
+        f.render_widget(para, summary_chunks[1]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(Entry::Answer(row)) => {
+                self.note = None;
+                app.edit_from_summary(row.page);
+            }
+            ListEvent::Chosen(Entry::Start) => {
+                if let Err(err) = self.validate(app) {
+                    self.note = Some(err);
+                    return Flow::Stay;
+                }
+                let steps = install::plan(app);
+                let state = Checkpoint::new(app.to_config());
+                if let Err(err) = state.save() {
+                    ctx.install.log.push(format!("warning: could not save resume state: {}", err));
+                }
+                ctx.install.start(steps, Vec::new(), state);
+                return Flow::Next;
+            }
+            ListEvent::Cancelled => return Flow::Back,
+            ListEvent::Ignored => match key.code {
+                KeyCode::Char('b') => {
+                    self.note = Some(match support::bundle(app, Path::new(".")) {
+                        Ok(path) => format!("Saved support bundle {}", path.display()),
+                        Err(err) => format!("Could not save support bundle: {}", err),
+                    });
+                }
+                KeyCode::Char('s') => {
+                    self.note = Some(match config::save(Path::new(config::DEFAULT_PATH), &app.to_config()) {
+                        Ok(()) => format!("Saved answers to {}", config::DEFAULT_PATH),
+                        Err(err) => format!("Could not save answers: {}", err),
+                    });
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        Flow::Stay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn enter_edits_the_selected_answer_or_refuses_to_start() {
+        let mut app = AppState { page: Page::Summary, ..AppState::default() };
+        let mut ctx = Context::default();
+        let mut page = Summary::default();
+        page.enter(&app);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        // Up wraps around to "Start installation", which is refused with missing answers.
+        page.handle_key(key(KeyCode::Up), &mut app, &mut ctx);
+        assert!(matches!(page.handle_key(key(KeyCode::Enter), &mut app, &mut ctx), Flow::Stay));
+        assert!(page.note.is_some());
+
+        page.handle_key(key(KeyCode::Home), &mut app, &mut ctx);
+        page.handle_key(key(KeyCode::Enter), &mut app, &mut ctx);
+        assert_eq!(app.page, Page::Profile);
+    }
+}
@@ -0,0 +1,205 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, GpuDriver, KernelSelection, Page};
+use crate::hardware;
+use crate::logging::{self, Kind};
+use crate::secret::Secret;
+use crate::widgets::{ListEvent, PasswordField, SelectList};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// Asks for a password twice; `prompt` also decides which one.
+pub struct PasswordPage {
+    page: Page,
+    prompt: &'static str,
+    password: PasswordField,
+    confirm: PasswordField,
+    /// 0 for the password, 1 for the confirmation.
+    focus: usize,
+    error: Option<String>,
+}
+
+impl PasswordPage {
+    pub fn new(page: Page, prompt: &'static str) -> Self {
+        PasswordPage { page, prompt, password: PasswordField::default(), confirm: PasswordField::default(), focus: 0, error: None }
+    }
+
+    fn focused(&mut self) -> &mut PasswordField {
+        if self.focus == 0 {
+            &mut self.password
+        } else {
+            &mut self.confirm
+        }
+    }
+
+    fn reset(&mut self) {
+        self.password.clear();
+        self.confirm.clear();
+        self.focus = 0;
+    }
+
+    fn current<'a>(&self, app: &'a AppState) -> Option<&'a Secret> {
+        if self.page == Page::RootPassword {
+            app.root_password.as_ref()
+        } else {
+            app.user_password.as_ref()
+        }
+    }
+}
+
+impl PageView for PasswordPage {
+    fn page(&self) -> Page {
+        self.page
+    }
+
+    fn enter(&mut self, _app: &AppState) {
+        self.reset();
+        self.error = None;
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Length(3), Constraint::Min(1)].as_ref())
+            .split(area);
+        self.password.render(f, chunks[0], self.prompt, self.focus == 0);
+        self.confirm.render(f, chunks[1], "Confirm", self.focus == 1);
+        let line = match &self.error {
+            Some(err) => Line::styled(err.as_str(), Style::default().fg(Color::Red)),
+            None if self.current(app).is_some() => Line::from("A password is set; leave both fields empty to keep it."),
+            None => Line::from("Enter moves to the confirmation and accepts; Tab switches fields."),
+        };
+        f.render_widget(Paragraph::new(line), chunks[2]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match key.code {
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => self.focus = 1 - self.focus,
+            KeyCode::Enter if self.password.is_empty() && self.confirm.is_empty() && self.current(app).is_some() => {
+                return Flow::Next;
+            }
+            KeyCode::Enter if self.focus == 0 => self.focus = 1,
+            KeyCode::Enter => {
+                if self.password.is_empty() {
+                    self.error = Some("The password cannot be empty.".to_string());
+                    self.focus = 0;
+                } else if !self.password.matches(&self.confirm) {
+                    self.error = Some("The passwords do not match.".to_string());
+                    self.reset();
+                } else {
+                    let pw = Some(self.password.take());
+                    self.reset();
+                    self.error = None;
+                    logging::log(Kind::Decision, format!("{} entered", self.prompt));
+                    if self.page == Page::RootPassword {
+                        app.root_password = pw;
+                    } else {
+                        app.user_password = pw;
+                    }
+                    return Flow::Next;
+                }
+            }
+            KeyCode::Esc => {
+                self.reset();
+                self.error = None;
+                return Flow::Back;
+            }
+            _ => {
+                self.focused().handle_key(key);
+            }
+        }
+        Flow::Stay
+    }
+
+    fn paste(&mut self, text: &str) {
+        self.focused().paste(text);
+    }
+}
+
+pub struct Kernel {
+    list: SelectList<KernelSelection>,
+}
+
+impl Default for Kernel {
+    fn default() -> Self {
+        Kernel { list: SelectList::new(KernelSelection::ALL.to_vec()) }
+    }
+}
+
+impl PageView for Kernel {
+    fn page(&self) -> Page {
+        Page::Kernel
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.list.select(&app.kernel.unwrap_or(KernelSelection::Both));
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        self.list.render(f, area, "Kernel to install");
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(kernel) => {
+                app.kernel = Some(kernel);
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
+
+pub struct Gpu {
+    list: SelectList<GpuDriver>,
+}
+
+impl Default for Gpu {
+    fn default() -> Self {
+        Gpu { list: SelectList::new(GpuDriver::ALL.to_vec()) }
+    }
+}
+
+impl PageView for Gpu {
+    fn page(&self) -> Page {
+        Page::GpuDriver
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.list.select(&app.gpu_driver.unwrap_or(GpuDriver::None));
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let gpu_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(6), Constraint::Length(app.detected_gpus.len() as u16 + 3)].as_ref())
+            .split(area);
+        self.list.render(f, gpu_chunks[0], "GPU Driver");
+
+        let mut lines: Vec<Line> = app.detected_gpus.iter().map(|g| Line::from(g.name.clone())).collect();
+        if lines.is_empty() {
+            lines.push(Line::from("No display controller found on the PCI bus."));
+        } else if hardware::is_hybrid(&app.detected_gpus) {
+            lines.push(Line::from("Hybrid graphics: the discrete GPU driver was preselected."));
+        }
+        let para = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Detected GPUs"));
+        f.render_widget(para, gpu_chunks[1]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match self.list.handle_key(key.code) {
+            ListEvent::Chosen(driver) => {
+                app.gpu_driver = Some(driver);
+                Flow::Next
+            }
+            ListEvent::Cancelled => Flow::Back,
+            _ => Flow::Stay,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, Page};
+use crate::widgets::TextInput;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{layout::Rect, Frame};
+
+/// A single free-text answer read by `get` and stored, trimmed, by `set`.
+pub struct TextPage {
+    page: Page,
+    title: &'static str,
+    get: fn(&AppState) -> Option<&str>,
+    set: fn(&mut AppState, String),
+    applies: fn(&AppState) -> bool,
+    required: bool,
+    input: TextInput,
+}
+
+impl TextPage {
+    pub fn new(page: Page, title: &'static str, get: fn(&AppState) -> Option<&str>, set: fn(&mut AppState, String)) -> Self {
+        TextPage { page, title, get, set, applies: |_| true, required: false, input: TextInput::default() }
+    }
+
+    /// Only asks the question when `applies` holds.
+    pub fn when(self, applies: fn(&AppState) -> bool) -> Self {
+        TextPage { applies, ..self }
+    }
+
+    /// Refuses to continue with an empty answer.
+    pub fn required(self) -> Self {
+        TextPage { required: true, ..self }
+    }
+}
+
+impl PageView for TextPage {
+    fn page(&self) -> Page {
+        self.page
+    }
+
+    fn is_applicable(&self, app: &AppState) -> bool {
+        (self.applies)(app)
+    }
+
+    fn enter(&mut self, app: &AppState) {
+        self.input.set((self.get)(app).unwrap_or(""));
+    }
+
+    fn validate(&self, app: &AppState) -> Result<(), String> {
+        if self.required && (self.get)(app).is_none_or(str::is_empty) {
+            return Err(format!("{} is required", self.title.split(" (").next().unwrap_or(self.title)));
+        }
+        Ok(())
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, _app: &AppState, _ctx: &Context) {
+        self.input.render(f, area, self.title, true);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        match key.code {
+            KeyCode::Esc => Flow::Back,
+            KeyCode::Backspace if self.input.is_empty() => Flow::Back,
+            KeyCode::Enter => {
+                (self.set)(app, self.input.value().trim().to_string());
+                Flow::Next
+            }
+            _ => {
+                self.input.handle_key(key);
+                Flow::Stay
+            }
+        }
+    }
+
+    fn paste(&mut self, text: &str) {
+        self.input.paste(text);
+    }
+}
@@ -0,0 +1,308 @@
+use super::{Context, Flow, PageView};
+use crate::app::{AppState, Page};
+use crate::secret::Secret;
+use crate::widgets::{Choice, ListEvent, PasswordField, SelectList};
+use crate::wifi::{AccessPoint, Iwctl, WifiBackend, WifiUpdate};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+impl Choice for AccessPoint {
+    fn label(&self) -> String {
+        let mark = if self.connected { ">" } else { " " };
+        let bars = format!("{:<4}", "*".repeat(self.bars()));
+        format!("{} {} {:>4} dBm  {:<6} {}", mark, bars, self.signal_dbm, self.security, self.ssid)
+    }
+}
+
+pub struct Wifi {
+    backend: Arc<dyn WifiBackend>,
+    rx: Option<Receiver<WifiUpdate>>,
+    access_points: SelectList<AccessPoint>,
+    interface: usize,
+    status: Option<String>,
+    /// The secured network being connected to while its passphrase is typed.
+    passphrase: Option<(AccessPoint, PasswordField)>,
+}
+
+impl Default for Wifi {
+    fn default() -> Self {
+        Wifi::new(Arc::new(Iwctl))
+    }
+}
+
+impl Wifi {
+    pub fn new(backend: Arc<dyn WifiBackend>) -> Self {
+        Wifi { backend, rx: None, access_points: SelectList::default(), interface: 0, status: None, passphrase: None }
+    }
+
+    fn connect(&mut self, interface: String, ap: AccessPoint, passphrase: Option<Secret>) {
+        self.status = Some(format!("Connecting to {}...", ap.ssid));
+        let backend = Arc::clone(&self.backend);
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let result = backend
+                .connect(&interface, &ap, passphrase.as_ref().map(Secret::expose))
+                .and_then(|_| backend.wait_online());
+            let _ = tx.send(WifiUpdate::Online(result));
+        });
+        self.rx = Some(rx);
+    }
+}
+
+impl PageView for Wifi {
+    fn page(&self) -> Page {
+        Page::Wifi
+    }
+
+    fn tick(&mut self, app: &mut AppState, _ctx: &mut Context) {
+        if let Some(update) = self.rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            match update {
+                WifiUpdate::Scanned(Ok(found)) => {
+                    self.status = Some(format!("{} networks found", found.len()));
+                    self.access_points = SelectList::new(found);
+                }
+                WifiUpdate::Scanned(Err(err)) => self.status = Some(format!("Scan failed: {}", err)),
+                WifiUpdate::Online(result) => {
+                    app.online = Some(result.is_ok());
+                    self.status = result.err();
+                }
+            }
+            self.rx = None;
+        }
+
+        // Check once on the Wi-Fi page so wired installs can continue straight away.
+        if app.page == Page::Wifi && app.online.is_none() && self.rx.is_none() {
+            let backend = Arc::clone(&self.backend);
+            let (tx, rx) = mpsc::channel();
+            thread::spawn(move || {
+                let _ = tx.send(WifiUpdate::Online(backend.wait_online()));
+            });
+            self.rx = Some(rx);
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, app: &AppState, _ctx: &Context) {
+        let page_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Length(4)].as_ref())
+            .split(area);
+        let wireless: Vec<&str> = app.detected_interfaces.iter().filter(|i| i.wireless).map(|i| i.name.as_str()).collect();
+        let interface = match wireless.get(self.interface) {
+            Some(name) => format!("< {} >", name),
+            None => "No wireless interfaces found".to_string(),
+        };
+        f.render_widget(
+            Paragraph::new(interface).block(Block::default().borders(Borders::ALL).title("Wireless interface")),
+            page_chunks[0],
+        );
+
+        self.access_points.render(f, page_chunks[1], "Networks (s: scan)");
+        if let Some((ap, field)) = &self.passphrase {
+            let title = format!("Passphrase for {} (Enter connects, Esc cancels)", ap.ssid);
+            field.render(f, page_chunks[2], &title, true);
+            return;
+        }
+
+        let state = match app.online {
+            _ if self.rx.is_some() => Line::from("Working..."),
+            Some(true) => Line::styled("Online", Style::default().fg(Color::LightGreen)),
+            Some(false) => Line::styled("Offline", Style::default().fg(Color::Red)),
+            None => Line::from("Checking connectivity..."),
+        };
+        let lines = vec![
+            state,
+            Line::from(self.status.clone().unwrap_or_else(|| {
+                "Left/Right: interface  s: scan  Enter: connect  c: check again  n: continue".to_string()
+            })),
+        ];
+        f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL)), page_chunks[2]);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut AppState, _ctx: &mut Context) -> Flow {
+        let wireless: Vec<String> = app.detected_interfaces.iter().filter(|i| i.wireless).map(|i| i.name.clone()).collect();
+        if let Some((ap, field)) = &mut self.passphrase {
+            match key.code {
+                KeyCode::Esc => self.passphrase = None,
+                KeyCode::Enter if !field.is_empty() => {
+                    let (ap, secret) = (ap.clone(), field.take());
+                    self.passphrase = None;
+                    if let Some(interface) = wireless.get(self.interface) {
+                        self.connect(interface.clone(), ap, Some(secret));
+                    }
+                }
+                _ => {
+                    field.handle_key(key);
+                }
+            }
+            return Flow::Stay;
+        }
+        let busy = self.rx.is_some();
+        match key.code {
+            KeyCode::Left | KeyCode::Right if !wireless.is_empty() => {
+                let len = wireless.len();
+                self.interface = if key.code == KeyCode::Right { (self.interface + 1) % len } else { (self.interface + len - 1) % len };
+                self.access_points = SelectList::default();
+            }
+            KeyCode::Char('s') if !busy && self.interface < wireless.len() => {
+                let interface = wireless[self.interface].clone();
+                self.status = Some(format!("Scanning on {}...", interface));
+                let backend = Arc::clone(&self.backend);
+                let (tx, rx) = mpsc::channel();
+                thread::spawn(move || {
+                    let _ = tx.send(WifiUpdate::Scanned(backend.scan(&interface)));
+                });
+                self.rx = Some(rx);
+            }
+            KeyCode::Char('c') if !busy => app.online = None,
+            KeyCode::Char('n') => {
+                self.status = None;
+                return Flow::Next;
+            }
+            KeyCode::Esc => return Flow::Back,
+            _ => {
+                if let ListEvent::Chosen(ap) = self.access_points.handle_key(key.code) {
+                    if busy || self.interface >= wireless.len() {
+                        return Flow::Stay;
+                    }
+                    if !ap.is_supported() {
+                        self.status = Some(format!("{} uses 802.1X, which is not supported; connect with iwctl first", ap.ssid));
+                    } else if ap.is_open() {
+                        self.connect(wireless[self.interface].clone(), ap, None);
+                    } else {
+                        self.passphrase = Some((ap, PasswordField::default()));
+                    }
+                }
+            }
+        }
+        Flow::Stay
+    }
+
+    fn paste(&mut self, text: &str) {
+        if let Some((_, field)) = &mut self.passphrase {
+            field.paste(text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Interface;
+    use crossterm::event::KeyModifiers;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    /// Records connections instead of running iwctl.
+    #[derive(Default)]
+    struct FakeBackend {
+        connects: Mutex<Vec<(String, String, Option<String>)>>,
+    }
+
+    impl WifiBackend for FakeBackend {
+        fn scan(&self, _interface: &str) -> Result<Vec<AccessPoint>, String> {
+            Ok(vec![
+                AccessPoint { ssid: "Home".into(), security: "psk".into(), signal_dbm: -45, connected: false },
+                AccessPoint { ssid: "Cafe".into(), security: "open".into(), signal_dbm: -70, connected: false },
+                AccessPoint { ssid: "Office".into(), security: "8021x".into(), signal_dbm: -75, connected: false },
+            ])
+        }
+
+        fn connect(&self, interface: &str, ap: &AccessPoint, passphrase: Option<&str>) -> Result<(), String> {
+            self.connects.lock().unwrap().push((interface.into(), ap.ssid.clone(), passphrase.map(String::from)));
+            Ok(())
+        }
+
+        fn wait_online(&self) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    fn press(page: &mut Wifi, app: &mut AppState, ctx: &mut Context, code: KeyCode) {
+        page.handle_key(KeyEvent::new(code, KeyModifiers::NONE), app, ctx);
+    }
+
+    /// Ticks until the background thread has reported back.
+    fn settle(page: &mut Wifi, app: &mut AppState, ctx: &mut Context) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while page.rx.is_some() && Instant::now() < deadline {
+            page.tick(app, ctx);
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn setup() -> (Arc<FakeBackend>, Wifi, AppState, Context) {
+        let backend = Arc::new(FakeBackend::default());
+        let page = Wifi::new(backend.clone());
+        let app = AppState {
+            page: Page::Wifi,
+            online: Some(false),
+            detected_interfaces: vec![Interface { name: "wlan0".into(), mac: None, wireless: true }],
+            ..AppState::default()
+        };
+        (backend, page, app, Context::default())
+    }
+
+    #[test]
+    fn scans_and_connects_with_passphrase() {
+        let (backend, mut page, mut app, mut ctx) = setup();
+        press(&mut page, &mut app, &mut ctx, KeyCode::Char('s'));
+        settle(&mut page, &mut app, &mut ctx);
+        assert_eq!(page.access_points.selected().map(|ap| ap.ssid.as_str()), Some("Home"));
+
+        press(&mut page, &mut app, &mut ctx, KeyCode::Enter);
+        assert!(page.passphrase.is_some());
+        page.paste("hunter22");
+        press(&mut page, &mut app, &mut ctx, KeyCode::Enter);
+        settle(&mut page, &mut app, &mut ctx);
+
+        assert_eq!(*backend.connects.lock().unwrap(), vec![("wlan0".into(), "Home".into(), Some("hunter22".into()))]);
+        assert_eq!(app.online, Some(true));
+    }
+
+    #[test]
+    fn connects_to_open_network_without_prompt() {
+        let (backend, mut page, mut app, mut ctx) = setup();
+        press(&mut page, &mut app, &mut ctx, KeyCode::Char('s'));
+        settle(&mut page, &mut app, &mut ctx);
+        press(&mut page, &mut app, &mut ctx, KeyCode::Down);
+        press(&mut page, &mut app, &mut ctx, KeyCode::Enter);
+        assert!(page.passphrase.is_none());
+        settle(&mut page, &mut app, &mut ctx);
+
+        assert_eq!(*backend.connects.lock().unwrap(), vec![("wlan0".into(), "Cafe".into(), None)]);
+    }
+
+    #[test]
+    fn cancelled_passphrase_does_not_connect() {
+        let (backend, mut page, mut app, mut ctx) = setup();
+        press(&mut page, &mut app, &mut ctx, KeyCode::Char('s'));
+        settle(&mut page, &mut app, &mut ctx);
+        press(&mut page, &mut app, &mut ctx, KeyCode::Enter);
+        press(&mut page, &mut app, &mut ctx, KeyCode::Esc);
+
+        assert!(page.passphrase.is_none());
+        assert!(backend.connects.lock().unwrap().is_empty());
+    }
+    #[test]
+    fn refuses_8021x_networks() {
+        let (backend, mut page, mut app, mut ctx) = setup();
+        press(&mut page, &mut app, &mut ctx, KeyCode::Char('s'));
+        settle(&mut page, &mut app, &mut ctx);
+        press(&mut page, &mut app, &mut ctx, KeyCode::End);
+        press(&mut page, &mut app, &mut ctx, KeyCode::Enter);
+
+        assert!(page.passphrase.is_none());
+        assert!(page.rx.is_none());
+        assert!(page.status.as_deref().is_some_and(|s| s.contains("not supported")));
+        assert!(backend.connects.lock().unwrap().is_empty());
+    }
+}
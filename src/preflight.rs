@@ -0,0 +1,152 @@
+use crate::mirrors::HttpClient;
+use crate::packages;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Present only on the official live ISO.
+const ARCHISO_MARKER: &str = "/run/archiso";
+const INSTALL_TOOLS: [&str; 3] = ["pacstrap", "genfstab", "arch-chroot"];
+const MIN_RAM_MIB: u64 = 512;
+const RECOMMENDED_RAM_MIB: u64 = 1024;
+const MIRROR_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// What to do about a warning or failure.
+    pub hint: Option<String>,
+}
+
+fn check(name: &'static str, status: Status, detail: impl Into<String>, hint: Option<&str>) -> Check {
+    Check { name, status, detail: detail.into(), hint: hint.filter(|_| status != Status::Pass).map(str::to_string) }
+}
+
+fn in_path(tool: &str) -> bool {
+    env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(tool).is_file()))
+}
+
+/// Install tools and the `mkfs` for `filesystem` that are not in `PATH`.
+pub fn missing_tools(filesystem: &str) -> Vec<String> {
+    let mkfs = format!("mkfs.{}", filesystem);
+    INSTALL_TOOLS
+        .iter()
+        .copied()
+        .chain([mkfs.as_str()])
+        .filter(|tool| !in_path(tool))
+        .map(str::to_string)
+        .collect()
+}
+
+fn field<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines().find_map(|l| l.strip_prefix(key)).map(str::trim)
+}
+
+fn check_root() -> Check {
+    let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+    let uid = field(&status, "Uid:").and_then(|ids| ids.split_whitespace().next()).unwrap_or("?");
+    if uid == "0" {
+        check("Running as root", Status::Pass, "uid 0", None)
+    } else {
+        check("Running as root", Status::Fail, format!("uid {}", uid), Some("Restart the installer as root."))
+    }
+}
+
+fn check_live_environment() -> Check {
+    if Path::new(ARCHISO_MARKER).exists() {
+        check("Arch live environment", Status::Pass, "booted from the Arch ISO", None)
+    } else {
+        check(
+            "Arch live environment",
+            Status::Warn,
+            format!("{} not found", ARCHISO_MARKER),
+            Some("Boot the official Arch ISO; other hosts need arch-install-scripts and may behave differently."),
+        )
+    }
+}
+
+fn check_tools(filesystem: &str) -> Check {
+    let missing = missing_tools(filesystem);
+    if missing.is_empty() {
+        return check("Required tools", Status::Pass, format!("{} and mkfs.{} found", INSTALL_TOOLS.join(", "), filesystem), None);
+    }
+    let hint = format!(
+        "Install them with: pacman -S arch-install-scripts {}",
+        packages::filesystem_packages(filesystem).join(" ")
+    );
+    check("Required tools", Status::Fail, format!("missing {}", missing.join(", ")), Some(&hint))
+}
+
+fn check_mirror(mirror_url: Option<&str>, client: &dyn HttpClient) -> Check {
+    let hint = Some("Connect on the Wi-Fi page or plug in a cable, or use an offline repository.");
+    let Some(url) = mirror_url else {
+        return check("Mirror reachable", Status::Warn, "no mirrors configured", hint);
+    };
+    match client.download(url, MIRROR_TIMEOUT) {
+        Ok(_) => check("Mirror reachable", Status::Pass, url, None),
+        Err(err) => check("Mirror reachable", Status::Warn, format!("{}: {}", url, err), hint),
+    }
+}
+
+fn check_clock() -> Check {
+    let output = Command::new("timedatectl").args(["show", "--property=NTPSynchronized", "--value"]).output();
+    match output.map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string()) {
+        Ok(value) if value == "yes" => check("Clock synchronized", Status::Pass, "NTP synchronized", None),
+        Ok(_) => check(
+            "Clock synchronized",
+            Status::Warn,
+            "not synchronized",
+            Some("Run `timedatectl set-ntp true`; a wrong clock makes package signatures look invalid."),
+        ),
+        Err(err) => check("Clock synchronized", Status::Warn, format!("timedatectl: {}", err), Some("Check the date with `date`.")),
+    }
+}
+
+fn check_memory() -> Check {
+    let meminfo = fs::read_to_string("/proc/meminfo").unwrap_or_default();
+    let Some(kib) = field(&meminfo, "MemTotal:").and_then(|v| v.split_whitespace().next()).and_then(|v| v.parse::<u64>().ok()) else {
+        return check("Memory", Status::Warn, "could not read /proc/meminfo", None);
+    };
+    let mib = kib / 1024;
+    let detail = format!("{} MiB", mib);
+    if mib < MIN_RAM_MIB {
+        check("Memory", Status::Fail, detail, Some("The live system needs at least 512 MiB of RAM."))
+    } else if mib < RECOMMENDED_RAM_MIB {
+        check("Memory", Status::Warn, detail, Some("Large desktops may fail to install; enable swap or pick a smaller profile."))
+    } else {
+        check("Memory", Status::Pass, detail, None)
+    }
+}
+
+/// Runs every check; the mirror test makes this take up to a few seconds.
+pub fn run(filesystem: &str, mirror_url: Option<&str>, client: &dyn HttpClient) -> Vec<Check> {
+    vec![
+        check_root(),
+        check_live_environment(),
+        check_tools(filesystem),
+        check_mirror(mirror_url, client),
+        check_clock(),
+        check_memory(),
+    ]
+}
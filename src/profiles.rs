@@ -0,0 +1,153 @@
+use crate::app::{AppState, DesktopEnv, GpuDriver, KernelSelection};
+use crate::config::Config;
+use std::fs;
+use std::path::Path;
+
+/// Directories searched for user-defined `*.conf` profiles.
+pub const PROFILE_DIRS: [&str; 2] = ["/etc/installarch/profiles", "profiles"];
+
+/// A named bundle of answers for a standard machine type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub description: String,
+    pub packages: Vec<String>,
+    pub services: Vec<String>,
+    /// Supplementary groups for the created user.
+    pub groups: Vec<String>,
+    pub desktop_env: Option<DesktopEnv>,
+    pub desktop_full: Option<bool>,
+    pub gpu_driver: Option<GpuDriver>,
+    pub kernel: Option<KernelSelection>,
+    pub multilib: Option<bool>,
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+pub fn builtin() -> Vec<Profile> {
+    vec![
+        Profile {
+            name: "minimal".into(),
+            description: "Base system on the stock kernel, no desktop".into(),
+            desktop_env: Some(DesktopEnv::None),
+            kernel: Some(KernelSelection::Linux),
+            ..Default::default()
+        },
+        Profile {
+            name: "developer".into(),
+            description: "Plasma workstation with compilers, containers and editors".into(),
+            packages: strings(&["git", "docker", "docker-compose", "python", "nodejs", "npm", "rustup", "go", "code", "vim"]),
+            services: strings(&["docker"]),
+            groups: strings(&["docker"]),
+            desktop_env: Some(DesktopEnv::KDE),
+            desktop_full: Some(false),
+            ..Default::default()
+        },
+        Profile {
+            name: "gaming".into(),
+            description: "Full Plasma desktop with gaming tools on the stock kernel".into(),
+            packages: strings(&["steam", "gamemode", "lib32-gamemode", "mangohud", "lutris", "pipewire-pulse"]),
+            groups: strings(&["gamemode"]),
+            desktop_env: Some(DesktopEnv::KDE),
+            desktop_full: Some(true),
+            kernel: Some(KernelSelection::Linux),
+            multilib: Some(true),
+            ..Default::default()
+        },
+        Profile {
+            name: "server".into(),
+            description: "Headless LTS system with SSH, firewall and fail2ban".into(),
+            packages: strings(&["openssh", "ufw", "fail2ban", "htop", "tmux"]),
+            services: strings(&["sshd", "ufw", "fail2ban"]),
+            desktop_env: Some(DesktopEnv::None),
+            gpu_driver: Some(GpuDriver::None),
+            kernel: Some(KernelSelection::LinuxLTS),
+            ..Default::default()
+        },
+    ]
+}
+
+impl Profile {
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let name = config.get("name").ok_or("missing `name`")?.to_string();
+        Ok(Profile {
+            description: config.get("description").unwrap_or_default().to_string(),
+            packages: config.get_list("packages"),
+            services: config.get_list("services"),
+            groups: config.get_list("groups"),
+            desktop_env: config.get_parsed("desktop", DesktopEnv::from_key)?,
+            desktop_full: config.get_bool("desktop_full")?,
+            gpu_driver: config.get_parsed("gpu", GpuDriver::from_key)?,
+            kernel: config.get_parsed("kernel", KernelSelection::from_key)?,
+            multilib: config.get_bool("multilib")?,
+            name,
+        })
+    }
+
+    /// Prefills the answers; every field stays editable on its own page.
+    pub fn apply(&self, app: &mut AppState) {
+        app.profile = Some(self.name.clone());
+        if let Some(desktop) = self.desktop_env {
+            app.set_desktop_env(desktop);
+        }
+        if self.desktop_full.is_some() {
+            app.desktop_full = self.desktop_full;
+        }
+        if self.gpu_driver.is_some() {
+            app.gpu_driver = self.gpu_driver;
+        }
+        if self.kernel.is_some() {
+            app.kernel = self.kernel;
+        }
+        if self.multilib.is_some() {
+            app.multilib = self.multilib;
+        }
+        app.extra_packages = self.packages.clone();
+        app.extra_services = self.services.clone();
+        app.user_groups = self.groups.clone();
+    }
+}
+
+/// Loads `*.conf` profiles from `dir`, returning the profiles that parsed and
+/// a message for each file that did not.
+pub fn load_dir(dir: &Path) -> (Vec<Profile>, Vec<String>) {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|p| p.extension().is_some_and(|ext| ext == "conf"));
+    paths.sort();
+
+    let mut profiles = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        let result = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Config::parse(&text))
+            .and_then(|config| Profile::from_config(&config));
+        match result {
+            Ok(profile) => profiles.push(profile),
+            Err(err) => errors.push(format!("{}: {}", path.display(), err)),
+        }
+    }
+    (profiles, errors)
+}
+
+/// Built-in profiles followed by user-defined ones; a user profile with the
+/// same name replaces the built-in.
+pub fn load_all() -> (Vec<Profile>, Vec<String>) {
+    let mut profiles = builtin();
+    let mut errors = Vec::new();
+    for dir in PROFILE_DIRS {
+        let (found, errs) = load_dir(Path::new(dir));
+        errors.extend(errs);
+        for profile in found {
+            match profiles.iter_mut().find(|p| p.name == profile.name) {
+                Some(existing) => *existing = profile,
+                None => profiles.push(profile),
+            }
+        }
+    }
+    (profiles, errors)
+}
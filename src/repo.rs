@@ -0,0 +1,193 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const SYNC_DIR: &str = "/var/lib/pacman/sync";
+const MAX_RESULTS: usize = 50;
+
+#[derive(Debug, Clone, Default)]
+pub struct RepoPackage {
+    pub repo: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub groups: Vec<String>,
+    pub provides: Vec<String>,
+}
+
+/// Package metadata read from the locally synced pacman databases.
+#[derive(Debug, Default)]
+pub struct SyncDb {
+    packages: Vec<RepoPackage>,
+}
+
+impl SyncDb {
+    /// Reads every `*.db` under `/var/lib/pacman/sync`. Databases that cannot
+    /// be read are skipped, so an unsynced system simply yields an empty index.
+    pub fn load() -> Self {
+        Self::load_from(Path::new(SYNC_DIR))
+    }
+
+    pub fn load_from(dir: &Path) -> Self {
+        let mut dbs: Vec<_> = fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        dbs.retain(|p| p.extension().is_some_and(|ext| ext == "db"));
+        dbs.sort();
+
+        let mut packages = Vec::new();
+        for db in dbs {
+            let repo = db.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            // The databases are compressed tarballs of `<pkg>/desc` files;
+            // bsdtar ships with pacman and handles both gzip and zstd.
+            let Ok(output) = Command::new("bsdtar").arg("-xOf").arg(&db).arg("*/desc").output() else {
+                continue;
+            };
+            if output.status.success() {
+                packages.extend(parse_desc(&String::from_utf8_lossy(&output.stdout), &repo));
+            }
+        }
+        Self { packages }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    /// Name matches first (prefix before substring), then description matches.
+    pub fn search(&self, query: &str) -> Vec<&RepoPackage> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(u8, &RepoPackage)> = self
+            .packages
+            .iter()
+            .filter_map(|p| {
+                let name = p.name.to_lowercase();
+                if name == query {
+                    Some((0, p))
+                } else if name.starts_with(&query) {
+                    Some((1, p))
+                } else if name.contains(&query) {
+                    Some((2, p))
+                } else if p.description.to_lowercase().contains(&query) {
+                    Some((3, p))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        scored.into_iter().take(MAX_RESULTS).map(|(_, p)| p).collect()
+    }
+
+    /// Whether `pacman -S <name>` can resolve the name: a package, a group or
+    /// something a package provides.
+    pub fn contains(&self, name: &str) -> bool {
+        self.packages.iter().any(|p| {
+            p.name == name
+                || p.groups.iter().any(|g| g == name)
+                || p.provides.iter().any(|prov| prov.split('=').next() == Some(name))
+        })
+    }
+}
+
+/// Parses concatenated `desc` entries (`%NAME%`, `%VERSION%`, ... sections).
+fn parse_desc(text: &str, repo: &str) -> Vec<RepoPackage> {
+    let mut packages = Vec::new();
+    let mut current = RepoPackage { repo: repo.to_string(), ..Default::default() };
+    let mut section = "";
+
+    for line in text.lines() {
+        if line.len() > 2 && line.starts_with('%') && line.ends_with('%') {
+            section = line;
+            if section == "%FILENAME%" && !current.name.is_empty() {
+                packages.push(std::mem::replace(
+                    &mut current,
+                    RepoPackage { repo: repo.to_string(), ..Default::default() },
+                ));
+            }
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        match section {
+            "%NAME%" => current.name = line.to_string(),
+            "%VERSION%" => current.version = line.to_string(),
+            "%DESC%" => current.description = line.to_string(),
+            "%GROUPS%" => current.groups.push(line.to_string()),
+            "%PROVIDES%" => current.provides.push(line.to_string()),
+            _ => {}
+        }
+    }
+    if !current.name.is_empty() {
+        packages.push(current);
+    }
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_concatenated_desc_files() {
+        let text = "\
+%FILENAME%
+firefox-125.0-1-x86_64.pkg.tar.zst
+
+%NAME%
+firefox
+
+%VERSION%
+125.0-1
+
+%DESC%
+Fast, Private & Safe Web Browser
+
+%PROVIDES%
+firefox-esr
+
+%FILENAME%
+plasma-desktop-6.0-1-x86_64.pkg.tar.zst
+
+%NAME%
+plasma-desktop
+
+%VERSION%
+6.0-1
+
+%GROUPS%
+plasma
+kde
+";
+        let packages = parse_desc(text, "extra");
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].repo, "extra");
+        assert_eq!(packages[0].name, "firefox");
+        assert_eq!(packages[0].version, "125.0-1");
+        assert_eq!(packages[0].description, "Fast, Private & Safe Web Browser");
+        assert_eq!(packages[0].provides, vec!["firefox-esr"]);
+        assert_eq!(packages[1].name, "plasma-desktop");
+        assert_eq!(packages[1].groups, vec!["plasma", "kde"]);
+        assert!(packages[1].description.is_empty());
+    }
+
+    #[test]
+    fn parses_single_desc_without_filename() {
+        let packages = parse_desc("%NAME%\nvim\n%VERSION%\n9.1-1\n", "extra");
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "vim");
+    }
+
+    #[test]
+    fn empty_desc_has_no_packages() {
+        assert!(parse_desc("", "core").is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::ptr;
+use std::sync::atomic::{self, Ordering};
+
+const REDACTED: &str = "********";
+
+/// A password or passphrase. The value is only reachable through `expose`,
+/// formats as `********` and is overwritten with zeros when dropped.
+///
+/// It can also be built up in place: the editing methods never leave a copy
+/// behind, zeroing the old buffer whenever they have to grow it.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret(Vec<u8>);
+
+impl Secret {
+    pub fn new(value: String) -> Self {
+        Secret(value.into_bytes())
+    }
+
+    /// An empty secret with room for `capacity` bytes, so typing a value of
+    /// up to that length never reallocates.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Secret(Vec::with_capacity(capacity))
+    }
+
+    pub fn expose(&self) -> &str {
+        // Only ever built from strings and edited on char boundaries, so
+        // always valid UTF-8.
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        self.insert_str(self.0.len(), text);
+    }
+
+    /// Inserts `text` at byte offset `at`, which must be a char boundary.
+    pub fn insert_str(&mut self, at: usize, text: &str) {
+        self.reserve(text.len());
+        self.0.extend_from_slice(text.as_bytes());
+        self.0[at..].rotate_right(text.len());
+    }
+
+    /// Removes the bytes in `start..end`, which must be char boundaries.
+    pub fn remove_range(&mut self, start: usize, end: usize) {
+        let len = self.0.len() - (end - start);
+        self.0[start..].rotate_left(end - start);
+        zero(&mut self.0[len..]);
+        self.0.truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        zero(&mut self.0);
+        self.0.clear();
+    }
+
+    /// Grows into a fresh buffer and zeroes the old one, rather than letting
+    /// `Vec` reallocate and free it with the value still in it.
+    fn reserve(&mut self, additional: usize) {
+        let needed = self.0.len() + additional;
+        if needed <= self.0.capacity() {
+            return;
+        }
+        let mut grown = Vec::with_capacity(needed.max(self.0.capacity() * 2));
+        grown.extend_from_slice(&self.0);
+        zero(&mut self.0);
+        self.0 = grown;
+    }
+}
+
+fn zero(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: `byte` is a valid, aligned, exclusive reference. The
+        // volatile write keeps the compiler from eliding the store.
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret::new(value)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({})", REDACTED)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        zero(&mut self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_in_place() {
+        let mut secret = Secret::with_capacity(4);
+        secret.push_str("pässword");
+        secret.insert_str(0, "my ");
+        secret.remove_range(3, 4);
+        assert_eq!(secret.expose(), "my ässword");
+        secret.clear();
+        assert!(secret.is_empty());
+    }
+
+    #[test]
+    fn stays_within_a_reserved_buffer() {
+        let mut secret = Secret::with_capacity(64);
+        let buffer = secret.0.as_ptr();
+        for c in "correct horse battery staple".chars() {
+            secret.push_str(c.encode_utf8(&mut [0; 4]));
+        }
+        secret.remove_range(0, 8);
+        assert_eq!(secret.expose(), "horse battery staple");
+        assert_eq!(secret.0.as_ptr(), buffer);
+    }
+
+    #[test]
+    fn never_formats_the_value() {
+        let secret = Secret::new("hunter2".into());
+        assert_eq!(format!("{} {:?}", secret, secret), "******** Secret(********)");
+    }
+}
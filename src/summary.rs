@@ -0,0 +1,157 @@
+use crate::app::{AppState, AurHelper, DesktopEnv, KernelSelection, NetworkStack, Page};
+use crate::hardware;
+use crate::pacman_conf;
+
+/// One answer on the summary page, edited on `page`.
+#[derive(Clone)]
+pub struct Row {
+    pub label: &'static str,
+    pub value: String,
+    pub page: Page,
+    /// Why the installation cannot start with this answer.
+    pub problem: Option<&'static str>,
+}
+
+fn row(label: &'static str, value: impl Into<String>, page: Page) -> Row {
+    Row { label, value: value.into(), page, problem: None }
+}
+
+fn yes_no(value: Option<bool>) -> &'static str {
+    match value {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "not set",
+    }
+}
+
+fn text(value: &Option<String>) -> &str {
+    value.as_deref().filter(|v| !v.is_empty()).unwrap_or("not set")
+}
+
+/// Required answers get a problem when they are missing or empty.
+fn required(mut row: Row, missing: bool, problem: &'static str) -> Row {
+    if missing {
+        row.problem = Some(problem);
+    }
+    row
+}
+
+fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(" ")
+    }
+}
+
+pub fn rows(app: &AppState) -> Vec<Row> {
+    let empty = |value: &Option<String>| value.as_deref().is_none_or(str::is_empty);
+    let desktop = app.desktop_env.unwrap_or(DesktopEnv::None);
+    let mut rows = vec![
+        row("Profile", app.profile.as_deref().unwrap_or("none"), Page::Profile),
+        // An online install needs a working connection.
+        required(
+            row(
+                "Connection",
+                match app.online {
+                    Some(true) => "online",
+                    Some(false) => "offline",
+                    None => "not checked",
+                },
+                Page::Wifi,
+            ),
+            app.offline_repo.is_none() && app.online != Some(true),
+            "connect on the Wi-Fi page or use an offline repository",
+        ),
+        required(
+            row("Install GRUB", yes_no(app.install_grub), Page::GrubInstall),
+            app.install_grub.is_none(),
+            "required",
+        ),
+        required(
+            row("EFI partition", text(&app.efi_partition), Page::EfiPartition),
+            hardware::is_uefi() && empty(&app.efi_partition),
+            "required when booted in UEFI mode",
+        ),
+        required(row("Root partition", text(&app.root_partition), Page::RootPartition), empty(&app.root_partition), "required"),
+        required(row("Format root", yes_no(app.format_root), Page::RootFormat), app.format_root.is_none(), "required"),
+        row("Filesystem", text(&app.filesystem), Page::Filesystem),
+        required(row("Locale", text(&app.locale), Page::Locale), empty(&app.locale), "required"),
+        required(row("Hostname", text(&app.hostname), Page::Hostname), empty(&app.hostname), "required"),
+        row(
+            "Network",
+            format!(
+                "{}, {} static addresses, copy Wi-Fi {}",
+                app.network_stack.unwrap_or(NetworkStack::NetworkManager).label(),
+                app.static_addresses.len(),
+                yes_no(app.copy_wifi)
+            ),
+            Page::Network,
+        ),
+        required(row("Username", text(&app.username), Page::Username), empty(&app.username), "required"),
+        required(
+            row("User password", if app.user_password.is_some() { "set" } else { "not set" }, Page::UserPassword),
+            app.user_password.is_none(),
+            "required",
+        ),
+        required(
+            row("Root password", if app.root_password.is_some() { "set" } else { "not set" }, Page::RootPassword),
+            app.root_password.is_none(),
+            "required",
+        ),
+        row(
+            "Kernel",
+            match app.kernel.unwrap_or(KernelSelection::Both) {
+                KernelSelection::Linux => "linux",
+                KernelSelection::LinuxLTS => "linux-lts",
+                KernelSelection::Both => "linux and linux-lts",
+            },
+            Page::Kernel,
+        ),
+        row("GPU driver", app.gpu_driver.map_or("not set", |d| d.label()), Page::GpuDriver),
+        row("Desktop", desktop.def().label, Page::DesktopEnv),
+    ];
+    if desktop != DesktopEnv::None {
+        rows.push(row("Desktop extras", yes_no(app.desktop_full), Page::DesktopExtras));
+    }
+    rows.extend([
+        row("Display manager", app.display_manager.map_or("not set", |dm| dm.label()), Page::DisplayManager),
+        row("Autologin", yes_no(app.autologin), Page::Autologin),
+        // Signing keys are fetched from a keyserver, which needs the network.
+        required(
+            row(
+                "pacman",
+                format!(
+                    "multilib {}, color {}, candy {}, {} parallel downloads, {} custom repositories",
+                    yes_no(app.multilib),
+                    yes_no(app.pacman_color),
+                    yes_no(app.i_love_candy),
+                    app.parallel_downloads.unwrap_or(pacman_conf::DEFAULT_PARALLEL_DOWNLOADS),
+                    app.custom_repos.len()
+                ),
+                Page::PacmanConf,
+            ),
+            app.offline_repo.is_some() && app.custom_repos.iter().any(|r| r.key_id.is_some()),
+            "repository signing keys cannot be fetched offline",
+        ),
+        row("Extra packages", list(&app.extra_packages), Page::ExtraPackages),
+        row(
+            "AUR",
+            match app.aur_helper.unwrap_or(AurHelper::None) {
+                AurHelper::None => "no helper".to_string(),
+                helper => format!("{}: {}", helper.label(), list(&app.aur_packages)),
+            },
+            Page::Aur,
+        ),
+        row(
+            "Mirrors",
+            match app.mirrorlist.first() {
+                Some(first) => format!("{} selected, first {}", app.mirrorlist.len(), first),
+                None => "live system default".to_string(),
+            },
+            Page::Mirrors,
+        ),
+        row("Offline repository", app.offline_repo.as_deref().unwrap_or("none (online install)"), Page::OfflineRepo),
+    ]);
+    rows
+}
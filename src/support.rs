@@ -0,0 +1,172 @@
+use crate::app::AppState;
+use crate::config::Config;
+use crate::hardware;
+use crate::logging;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Config keys that identify the person or machine; blanked in bundles.
+const REDACTED_KEYS: [&str; 3] = ["username", "hostname", "static_addresses"];
+
+/// The answers without anything identifying. Passwords are never part of
+/// the config in the first place.
+pub fn redacted_config(app: &AppState) -> Config {
+    let mut config = app.to_config();
+    for key in REDACTED_KEYS {
+        if config.get(key).is_some_and(|v| !v.is_empty()) {
+            config.set(key, "<redacted>");
+        }
+    }
+    config
+}
+
+/// The log with every redacted answer replaced, so `decision` lines and
+/// commands such as `useradd` do not give them away. Only whole tokens (and
+/// components of absolute paths) are replaced, so a hostname like `arch`
+/// leaves `arch-chroot` and mirror URLs alone. Answers still at their
+/// built-in default, such as the hostname `archlinux`, identify no one and
+/// are kept.
+pub fn redacted_log(log: &str, app: &AppState) -> String {
+    let config = app.to_config();
+    let defaults = AppState::default().to_config();
+    let values: Vec<&str> = REDACTED_KEYS
+        .iter()
+        .filter_map(|key| config.get(key).filter(|value| defaults.get(key) != Some(*value)))
+        .flat_map(|value| std::iter::once(value).chain(value.split_whitespace()))
+        .filter(|value| !value.is_empty())
+        .collect();
+    if values.is_empty() {
+        return log.to_string();
+    }
+    let mut out = String::with_capacity(log.len());
+    let mut token = String::new();
+    for c in log.chars() {
+        if is_separator(c) {
+            out.push_str(&redact_token(&token, &values));
+            token.clear();
+            out.push(c);
+        } else {
+            token.push(c);
+        }
+    }
+    out.push_str(&redact_token(&token, &values));
+    out
+}
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '=' | '"' | '\'' | ',')
+}
+
+fn redact_token(token: &str, values: &[&str]) -> String {
+    if values.contains(&token) {
+        "<redacted>".to_string()
+    } else if token.starts_with('/') {
+        token
+            .split('/')
+            .map(|part| if values.contains(&part) { "<redacted>" } else { part })
+            .collect::<Vec<_>>()
+            .join("/")
+    } else {
+        token.to_string()
+    }
+}
+
+fn hardware_report(app: &AppState) -> String {
+    let mut report = format!("uefi: {}\n", hardware::is_uefi());
+    report.push_str(&format!("cpu: {:?}\n", app.cpu_vendor));
+    for gpu in &app.detected_gpus {
+        report.push_str(&format!("gpu: {:?} [{:04x}:{:04x}] {}\n", gpu.vendor, gpu.vendor_id, gpu.device_id, gpu.name));
+    }
+    for interface in &app.detected_interfaces {
+        report.push_str(&format!("interface: {} wireless={}\n", interface.name, interface.wireless));
+    }
+    report
+}
+
+fn command_output(args: &[&str]) -> String {
+    match Command::new(args[0]).args(&args[1..]).output() {
+        Ok(output) => format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)),
+        Err(err) => format!("{}: {}\n", args[0], err),
+    }
+}
+
+/// Writes `installarch-support-<time>.tar.gz` into `dir` with the redacted
+/// log and answers, `lsblk` and the hardware probe results.
+pub fn bundle(app: &AppState, dir: &Path) -> Result<PathBuf, String> {
+    let name = format!("installarch-support-{}", logging::file_timestamp());
+    let staging = std::env::temp_dir().join(&name);
+    fs::create_dir_all(&staging).map_err(|e| format!("{}: {}", staging.display(), e))?;
+
+    let write = |file: &str, contents: &str| {
+        let path = staging.join(file);
+        fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))
+    };
+    write("install.log", &redacted_log(&fs::read_to_string(logging::LOG_PATH).unwrap_or_default(), app))?;
+    write("installarch.conf", &redacted_config(app).to_string())?;
+    write("lsblk.txt", &command_output(&["lsblk", "-o", "NAME,SIZE,TYPE,FSTYPE,MOUNTPOINTS,MODEL"]))?;
+    write("hardware.txt", &hardware_report(app))?;
+
+    let archive = dir.join(format!("{}.tar.gz", name));
+    let output = Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(std::env::temp_dir())
+        .arg(&name)
+        .output()
+        .map_err(|e| format!("tar: {}", e))?;
+    let _ = fs::remove_dir_all(&staging);
+    if !output.status.success() {
+        return Err(format!("tar failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    logging::log(logging::Kind::Info, format!("saved support bundle {}", archive.display()));
+    Ok(archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::StaticAddress;
+
+    #[test]
+    fn redacts_answers_from_the_log() {
+        let app = AppState {
+            username: Some("alice".into()),
+            hostname: Some("arch".into()),
+            static_addresses: vec![StaticAddress::parse("eth0 192.168.1.10/24").unwrap()],
+            ..AppState::default()
+        };
+        let log = "decision username = alice\n\
+                   decision hostname = arch\n\
+                   decision static_addresses = eth0|192.168.1.10/24||\n\
+                   command arch-chroot /mnt useradd -m alice\n\
+                   command arch-chroot /mnt chown alice /home/alice/alice2\n";
+        assert_eq!(
+            redacted_log(log, &app),
+            "decision username = <redacted>\n\
+             decision hostname = <redacted>\n\
+             decision static_addresses = <redacted>\n\
+             command arch-chroot /mnt useradd -m <redacted>\n\
+             command arch-chroot /mnt chown <redacted> /home/<redacted>/alice2\n"
+        );
+    }
+
+    #[test]
+    fn leaves_urls_and_default_answers_alone() {
+        let app = AppState { hostname: Some("mirror".into()), ..AppState::default() };
+        let log = "decision username = user\n\
+                   command arch-chroot /mnt useradd -m user\n\
+                   command echo archlinux > /mnt/etc/hostname\n\
+                   Server = https://mirror.example.org/archlinux/$repo/os/$arch\n\
+                   decision hostname = mirror\n";
+        assert_eq!(
+            redacted_log(log, &app),
+            "decision username = user\n\
+             command arch-chroot /mnt useradd -m user\n\
+             command echo archlinux > /mnt/etc/hostname\n\
+             Server = https://mirror.example.org/archlinux/$repo/os/$arch\n\
+             decision hostname = <redacted>\n"
+        );
+    }
+}
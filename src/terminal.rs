@@ -0,0 +1,105 @@
+// Terminal setup and guaranteed restoration.
+//
+// Raw mode and the alternate screen are undone when the guard drops, when
+// the UI thread panics and, via a flag polled by the UI loop, on SIGINT,
+// SIGTERM and SIGHUP. A running installation is stopped cleanly before
+// exiting. Panics on background threads leave the UI running; they are
+// logged, and the installation reports them as a failed step.
+
+use crate::logging::{self, Kind};
+use crossterm::cursor::Show;
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::io::{self, stdout, Stdout};
+use std::os::raw::c_int;
+use std::panic;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread;
+
+const SIGHUP: c_int = 1;
+const SIGINT: c_int = 2;
+const SIGTERM: c_int = 15;
+
+/// The last signal received, 0 once taken.
+static SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+extern "C" {
+    fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+}
+
+/// Only records the signal: nothing else is async-signal-safe.
+extern "C" fn on_signal(signum: c_int) {
+    SIGNAL.store(signum, Ordering::SeqCst);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// SIGINT, which asks the user first while installing.
+    Interrupt,
+    /// SIGTERM or SIGHUP.
+    Terminate,
+}
+
+/// The signal received since the last call, if any. The UI loop reacts by
+/// exiting normally so the guard can restore the terminal.
+pub fn take_signal() -> Option<Signal> {
+    match SIGNAL.swap(0, Ordering::SeqCst) {
+        0 => None,
+        SIGINT => Some(Signal::Interrupt),
+        _ => Some(Signal::Terminate),
+    }
+}
+
+/// Leaves the alternate screen and raw mode. Safe to call more than once.
+pub fn restore() {
+    let _ = disable_raw_mode();
+    let _ = execute!(stdout(), DisableBracketedPaste, LeaveAlternateScreen, Show);
+}
+
+pub struct TerminalGuard {
+    pub terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl TerminalGuard {
+    pub fn new() -> io::Result<Self> {
+        let default_hook = panic::take_hook();
+        let ui_thread = thread::current().id();
+        panic::set_hook(Box::new(move |info| {
+            if thread::current().id() != ui_thread {
+                // Printing would draw over the UI, which keeps running.
+                logging::log(Kind::Error, format!("background thread {}", info));
+                return;
+            }
+            // Restore first so the message is readable on the normal screen.
+            restore();
+            default_hook(info);
+        }));
+        for signum in [SIGHUP, SIGINT, SIGTERM] {
+            // SAFETY: `on_signal` has the C signature `signal` expects and
+            // only touches an atomic.
+            unsafe { signal(signum, on_signal) };
+        }
+
+        enable_raw_mode()?;
+        if let Err(err) = execute!(stdout(), EnterAlternateScreen, EnableBracketedPaste) {
+            restore();
+            return Err(err);
+        }
+        match Terminal::new(CrosstermBackend::new(stdout())) {
+            Ok(terminal) => Ok(TerminalGuard { terminal }),
+            Err(err) => {
+                restore();
+                Err(err)
+            }
+        }
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
@@ -1,42 +1,90 @@
-use crate::app::{AppState, DesktopEnv, GpuDriver, KernelSelection, Page};
-use crossterm::event::{self, Event, KeyCode};
+use crate::app::{AppState, Page};
+use crate::config;
+use crate::logging::{self, Kind};
+use crate::pages::{Context, Flow, Registry};
+use crate::terminal::{self, Signal};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    text::{Span, Line},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
-    Frame, Terminal,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Terminal,
 };
 use std::io;
+use std::path::Path;
 
-fn render_text_input(f: &mut Frame, area: Rect, title: &str, value: &str, selected: bool) {
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title(Span::styled(title, Style::default().fg(Color::Yellow)));
-    let para = Paragraph::new(value.to_string()).block(block);
-    f.render_widget(para, area);
-    if selected {
-        f.set_cursor(area.x + value.len() as u16 + 1, area.y + 1);
-    }
+/// What the quit dialog offers.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum QuitDialog {
+    /// Quit the wizard, optionally saving the answers first.
+    Wizard,
+    /// Abort the running installation at the next step boundary, or quit now.
+    Install,
 }
 
-fn render_selection_list(f: &mut Frame, area: Rect, title: &str, items: &[&str], selected_index: usize) {
-    let list_items: Vec<ListItem> = items.iter().map(|i| ListItem::new(*i)).collect();
-    let mut state = ListState::default();
-    state.select(Some(selected_index));
-    let list = List::new(list_items)
-        .block(Block::default().title(title).borders(Borders::ALL))
-        .highlight_style(Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD))
-        .highlight_symbol(">> ");
-    f.render_stateful_widget(list, area, &mut state);
+/// A box of `width` x `height` cells centred in `area`.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
 }
 
 pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut AppState) -> io::Result<()> {
-    let mut input = String::new();
-    let mut select_idx = 0;
+    let mut registry = Registry::default();
+    let mut ctx = Context::default();
+    // Why the current page refused to continue.
+    let mut page_error: Option<String> = None;
+    let mut quit_dialog: Option<QuitDialog> = None;
+    let mut quit_note: Option<String> = None;
+    // A termination signal arrived during the install; exit once it stops.
+    let mut terminating = false;
+    let mut last_page = app.page;
+    let mut last_config = app.to_config();
+    logging::log(Kind::Page, format!("{:?}", app.page));
+    registry.enter(app);
 
     loop {
+        if let Some(signal) = terminal::take_signal() {
+            let installing = app.page == Page::Installing && ctx.install.error.is_none();
+            match signal {
+                Signal::Interrupt if installing => quit_dialog = Some(QuitDialog::Install),
+                Signal::Terminate if installing => {
+                    logging::log(Kind::Info, "terminated by signal, aborting the installation first");
+                    ctx.install.abort();
+                    terminating = true;
+                }
+                _ => {
+                    logging::log(Kind::Info, "terminated by signal");
+                    break;
+                }
+            }
+        }
+
+        // Every transition is logged together with the answers it changed.
+        if app.page != last_page {
+            logging::log(Kind::Page, format!("{:?} -> {:?}", last_page, app.page));
+            let config = app.to_config();
+            for (key, value) in config.iter() {
+                if last_config.get(key) != Some(value) {
+                    logging::log(Kind::Decision, format!("{} = {}", key, value));
+                }
+            }
+            last_page = app.page;
+            last_config = config;
+            registry.enter(app);
+        }
+
+        // Checked before ticking so the final progress, including the last
+        // checkpoint, is collected before exiting.
+        let stopped = terminating && !ctx.install.is_running();
+        registry.tick(app, &mut ctx);
+        if stopped {
+            logging::log(Kind::Info, "installation stopped, exiting");
+            break;
+        }
+
         terminal.draw(|f| {
             let size = f.size();
             let chunks = Layout::default()
@@ -49,305 +97,93 @@ pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut AppState) -> io
             let title_block = Block::default().title(title).borders(Borders::ALL);
             f.render_widget(title_block, size);
 
-            match app.page {
-                Page::Welcome => {
-                    let text = vec![
-                        Line::from("Welcome to the Arch Linux Installer TUI!"),
-                        Line::from("Press Enter to start."),
-                    ];
-                    let para = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Welcome"));
-                    f.render_widget(para, chunks[1]);
-                }
-                Page::GrubInstall => {
-                    let options = ["Yes", "No"];
-                    render_selection_list(f, chunks[1], "Install GRUB bootloader?", &options, select_idx);
-                }
-                Page::EfiPartition => {
-                    let val = app.efi_partition.as_deref().unwrap_or("");
-                    render_text_input(f, chunks[1], "EFI Partition (e.g. /dev/sda1)", val, true);
-                }
-                Page::RootPartition => {
-                    let val = app.root_partition.as_deref().unwrap_or("");
-                    render_text_input(f, chunks[1], "Root Partition (e.g. /dev/sda2)", val, true);
-                }
-                Page::RootFormat => {
-                    let options = ["Yes", "No"];
-                    render_selection_list(f, chunks[1], "Format root partition?", &options, select_idx);
-                }
-                Page::Filesystem => {
-                    let options = ["ext4", "btrfs", "xfs"];
-                    let current_fs = app.filesystem.as_deref().unwrap_or("ext4");
-                    let idx = options.iter().position(|&x| x == current_fs).unwrap_or(0);
-                    render_selection_list(f, chunks[1], "Filesystem type", &options, idx);
-                }
-                Page::Locale => {
-                    let val = app.locale.as_deref().unwrap_or("");
-                    render_text_input(f, chunks[1], "Locale (e.g. en_US.UTF-8)", val, true);
-                }
-                Page::Hostname => {
-                    let val = app.hostname.as_deref().unwrap_or("");
-                    render_text_input(f, chunks[1], "Hostname", val, true);
-                }
-                Page::Username => {
-                    let val = app.username.as_deref().unwrap_or("");
-                    render_text_input(f, chunks[1], "Username", val, true);
-                }
-                Page::UserPassword => {
-                    let text = vec![Line::from("Press Enter to enter your user password.")];
-                    let para = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("User Password"));
-                    f.render_widget(para, chunks[1]);
-                }
-                Page::RootPassword => {
-                    let text = vec![Line::from("Press Enter to enter the root password.")];
-                    let para = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Root Password"));
-                    f.render_widget(para, chunks[1]);
-                }
-                Page::Kernel => {
-                    let options = ["linux", "linux-lts", "both"];
-                    let idx = match app.kernel.unwrap_or(KernelSelection::Both) {
-                        KernelSelection::Linux => 0,
-                        KernelSelection::LinuxLTS => 1,
-                        KernelSelection::Both => 2,
-                    };
-                    render_selection_list(f, chunks[1], "Kernel to install", &options, idx);
-                }
-                Page::GpuDriver => {
-                    let options = ["Intel", "AMD", "NVIDIA", "None"];
-                    let idx = match app.gpu_driver.unwrap_or(GpuDriver::None) {
-                        GpuDriver::Intel => 0,
-                        GpuDriver::AMD => 1,
-                        GpuDriver::Nvidia => 2,
-                        GpuDriver::None => 3,
-                    };
-                    render_selection_list(f, chunks[1], "GPU Driver", &options, idx);
-                }
-                Page::DesktopEnv => {
-                    let options = ["None", "KDE Plasma", "GNOME", "GNOME lock screen + KDE"];
-                    let idx = match app.desktop_env.unwrap_or(DesktopEnv::None) {
-                        DesktopEnv::None => 0,
-                        DesktopEnv::KDE => 1,
-                        DesktopEnv::GNOME => 2,
-                        DesktopEnv::GnomeLockKDE => 3,
-                    };
-                    render_selection_list(f, chunks[1], "Desktop Environment", &options, idx);
-                }
-                Page::Summary => {
-                    let mut lines = Vec::new();
-                    lines.push(Line::from(format!("Install GRUB: {:?}", app.install_grub)));
-                    lines.push(Line::from(format!("EFI Partition: {:?}", app.efi_partition)));
-                    lines.push(Line::from(format!("Root Partition: {:?}", app.root_partition)));
-                    lines.push(Line::from(format!("Format root: {:?}", app.format_root)));
-                    lines.push(Line::from(format!("Filesystem: {:?}", app.filesystem)));
-                    lines.push(Line::from(format!("Locale: {:?}", app.locale)));
-                    lines.push(Line::from(format!("Hostname: {:?}", app.hostname)));
-                    lines.push(Line::from(format!("Username: {:?}", app.username)));
-                    lines.push(Line::from(format!("Kernel: {:?}", app.kernel)));
-                    lines.push(Line::from(format!("GPU Driver: {:?}", app.gpu_driver)));
-                    lines.push(Line::from(format!("Desktop Environment: {:?}", app.desktop_env)));
-                    lines.push(Line::from("Press Enter to confirm and start installation."));
-                    let para = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Summary"));
-                    f.render_widget(para, chunks[1]);
-                }
-                Page::Finished => {
-                    let text = vec![Line::from("Installation finished! Please reboot your system.")];
-                    let para = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Finished"));
-                    f.render_widget(para, chunks[1]);
-                }
+            registry.get(app.page).render(f, chunks[1], app, &ctx);
+
+            if let Some(err) = &page_error {
+                f.render_widget(Paragraph::new(Line::styled(err.as_str(), Style::default().fg(Color::Red))), chunks[2]);
+            }
+
+            if let Some(dialog) = quit_dialog {
+                let mut text = match dialog {
+                    QuitDialog::Wizard => vec![
+                        Line::from("Quit the installer?"),
+                        Line::from(format!("s: save answers to {} and quit", config::DEFAULT_PATH)),
+                        Line::from("y: quit without saving"),
+                        Line::from("any other key: keep going"),
+                    ],
+                    QuitDialog::Install => vec![
+                        Line::from("Installation in progress."),
+                        Line::from("a: abort after the current step"),
+                        Line::from("y: quit now, leaving the target half-installed"),
+                        Line::from("any other key: keep going"),
+                    ],
+                };
+                if let Some(note) = &quit_note {
+                    text.push(Line::styled(note.as_str(), Style::default().fg(Color::Red)));
+                }
+                let area = centered(f.size(), 60, text.len() as u16 + 2);
+                let para = Paragraph::new(text)
+                    .style(Style::default().fg(Color::Yellow))
+                    .block(Block::default().borders(Borders::ALL).title("Quit"));
+                f.render_widget(Clear, area);
+                f.render_widget(para, area);
             }
         })?;
 
         if event::poll(std::time::Duration::from_millis(200))? {
-            match event::read()? {
-                Event::Key(key) => {
-                    match app.page {
-                        Page::Welcome => {
-                            if key.code == KeyCode::Enter {
-                                app.next_page();
-                                select_idx = 0;
-                            }
-                        }
-                        Page::GrubInstall | Page::RootFormat => {
-                            let options_len = 2;
-                            match key.code {
-                                KeyCode::Up => {
-                                    select_idx = (select_idx + options_len - 1) % options_len;
-                                }
-                                KeyCode::Down => {
-                                    select_idx = (select_idx + 1) % options_len;
-                                }
-                                KeyCode::Enter => {
-                                    let val = select_idx == 0;
-                                    match app.page {
-                                        Page::GrubInstall => app.install_grub = Some(val),
-                                        Page::RootFormat => app.format_root = Some(val),
-                                        _ => {}
-                                    }
-                                    select_idx = 0;
-                                    app.next_page();
-                                }
-                                KeyCode::Esc | KeyCode::Backspace => {
-                                    app.prev_page();
-                                    select_idx = 0;
-                                }
-                                _ => {}
-                            }
-                        }
-                        Page::Filesystem => {
-                            let options = ["ext4", "btrfs", "xfs"];
-                            let options_len = options.len();
-                            match key.code {
-                                KeyCode::Up => select_idx = (select_idx + options_len - 1) % options_len,
-                                KeyCode::Down => select_idx = (select_idx + 1) % options_len,
-                                KeyCode::Enter => {
-                                    app.filesystem = Some(options[select_idx].to_string());
-                                    select_idx = 0;
-                                    app.next_page();
-                                }
-                                KeyCode::Esc | KeyCode::Backspace => {
-                                    app.prev_page();
-                                    select_idx = 0;
-                                }
-                                _ => {}
-                            }
-                        }
-                        Page::Kernel => {
-                            let options = ["linux", "linux-lts", "both"];
-                            let options_len = options.len();
-                            match key.code {
-                                KeyCode::Up => select_idx = (select_idx + options_len - 1) % options_len,
-                                KeyCode::Down => select_idx = (select_idx + 1) % options_len,
-                                KeyCode::Enter => {
-                                    app.kernel = match select_idx {
-                                        0 => Some(KernelSelection::Linux),
-                                        1 => Some(KernelSelection::LinuxLTS),
-                                        2 => Some(KernelSelection::Both),
-                                        _ => None,
-                                    };
-                                    select_idx = 0;
-                                    app.next_page();
-                                }
-                                KeyCode::Esc | KeyCode::Backspace => {
-                                    app.prev_page();
-                                    select_idx = 0;
-                                }
-                                _ => {}
-                            }
-                        }
-                        Page::GpuDriver => {
-                            let options = ["Intel", "AMD", "NVIDIA", "None"];
-                            let options_len = options.len();
-                            match key.code {
-                                KeyCode::Up => select_idx = (select_idx + options_len - 1) % options_len,
-                                KeyCode::Down => select_idx = (select_idx + 1) % options_len,
-                                KeyCode::Enter => {
-                                    app.gpu_driver = match select_idx {
-                                        0 => Some(GpuDriver::Intel),
-                                        1 => Some(GpuDriver::AMD),
-                                        2 => Some(GpuDriver::Nvidia),
-                                        3 => Some(GpuDriver::None),
-                                        _ => None,
-                                    };
-                                    select_idx = 0;
-                                    app.next_page();
-                                }
-                                KeyCode::Esc | KeyCode::Backspace => {
-                                    app.prev_page();
-                                    select_idx = 0;
-                                }
-                                _ => {}
-                            }
-                        }
-                        Page::DesktopEnv => {
-                            let options = ["None", "KDE Plasma", "GNOME", "GNOME lock screen + KDE"];
-                            let options_len = options.len();
-                            match key.code {
-                                KeyCode::Up => select_idx = (select_idx + options_len - 1) % options_len,
-                                KeyCode::Down => select_idx = (select_idx + 1) % options_len,
-                                KeyCode::Enter => {
-                                    app.desktop_env = match select_idx {
-                                        0 => Some(DesktopEnv::None),
-                                        1 => Some(DesktopEnv::KDE),
-                                        2 => Some(DesktopEnv::GNOME),
-                                        3 => Some(DesktopEnv::GnomeLockKDE),
-                                        _ => None,
-                                    };
-                                    select_idx = 0;
-                                    app.next_page();
-                                }
-                                KeyCode::Esc | KeyCode::Backspace => {
-                                    app.prev_page();
-                                    select_idx = 0;
-                                }
-                                _ => {}
-                            }
+            let event = event::read()?;
+            if let Event::Paste(text) = &event {
+                if quit_dialog.is_none() {
+                    registry.get_mut(app.page).paste(text);
+                }
+            }
+            if let Event::Key(key) = event {
+                let installing = app.page == Page::Installing && ctx.install.error.is_none();
+                if let Some(dialog) = quit_dialog {
+                    match (dialog, key.code) {
+                        (_, KeyCode::Char('y')) => {
+                            logging::log(Kind::Info, format!("quit on {:?}", app.page));
+                            break;
                         }
-                        Page::EfiPartition | Page::RootPartition | Page::Locale | Page::Hostname | Page::Username => {
-                            match key.code {
-                                KeyCode::Char(c) => {
-                                    input.push(c);
-                                }
-                                KeyCode::Backspace => {
-                                    input.pop();
-                                }
-                                KeyCode::Enter => {
-                                    match app.page {
-                                        Page::EfiPartition => app.efi_partition = Some(input.trim().to_string()),
-                                        Page::RootPartition => app.root_partition = Some(input.trim().to_string()),
-                                        Page::Locale => app.locale = Some(input.trim().to_string()),
-                                        Page::Hostname => app.hostname = Some(input.trim().to_string()),
-                                        Page::Username => app.username = Some(input.trim().to_string()),
-                                        _ => {}
-                                    }
-                                    input.clear();
-                                    app.next_page();
-                                    select_idx = 0;
+                        (QuitDialog::Wizard, KeyCode::Char('s')) => {
+                            match config::save(Path::new(config::DEFAULT_PATH), &app.to_config()) {
+                                Ok(()) => {
+                                    logging::log(Kind::Info, format!("saved answers to {} and quit", config::DEFAULT_PATH));
+                                    break;
                                 }
-                                KeyCode::Esc | KeyCode::Backspace if input.is_empty() => {
-                                    app.prev_page();
-                                    select_idx = 0;
+                                Err(err) => {
+                                    quit_note = Some(format!("Could not save answers: {}", err));
+                                    continue;
                                 }
-                                _ => {}
-                            }
-                        }
-                        Page::UserPassword => {
-                            if key.code == KeyCode::Enter {
-                                app.prompt_password("User Password");
-                                app.next_page();
-                                select_idx = 0;
-                            } else if key.code == KeyCode::Esc {
-                                app.prev_page();
-                                select_idx = 0;
-                            }
-                        }
-                        Page::RootPassword => {
-                            if key.code == KeyCode::Enter {
-                                app.prompt_password("Root Password");
-                                app.next_page();
-                                select_idx = 0;
-                            } else if key.code == KeyCode::Esc {
-                                app.prev_page();
-                                select_idx = 0;
-                            }
-                        }
-                        Page::Summary => {
-                            if key.code == KeyCode::Enter {
-                                app.next_page();
-                                select_idx = 0;
-                            } else if key.code == KeyCode::Esc {
-                                app.prev_page();
-                                select_idx = 0;
-                            }
-                        }
-                        Page::Finished => {
-                            if key.code == KeyCode::Esc || key.code == KeyCode::Char('q') {
-                                break;
                             }
                         }
+                        (QuitDialog::Install, KeyCode::Char('a')) if installing => ctx.install.abort(),
+                        _ => {}
                     }
+                    quit_dialog = None;
+                    quit_note = None;
+                    continue;
+                }
+                let quit_key = key.modifiers.contains(KeyModifiers::CONTROL)
+                    && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('q'));
+                if quit_key {
+                    quit_dialog = Some(if installing { QuitDialog::Install } else { QuitDialog::Wizard });
+                    continue;
+                }
+
+                page_error = None;
+                match registry.get_mut(app.page).handle_key(key, app, &mut ctx) {
+                    Flow::Stay => {}
+                    Flow::Next => match registry.get(app.page).validate(app) {
+                        Ok(()) => registry.next(app),
+                        Err(err) => page_error = Some(err),
+                    },
+                    Flow::Back => registry.prev(app),
+                    Flow::ConfirmQuit => quit_dialog = Some(QuitDialog::Install),
+                    Flow::Quit => break,
                 }
-                _ => {}
             }
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,468 @@
+// Reusable input widgets. Each widget keeps its own cursor, turns key
+// events into a small set of outcomes and renders itself; the pages decide
+// what an outcome means for the answers.
+
+use crate::app::{AurHelper, DesktopEnv, DisplayManager, GpuDriver, KernelSelection, NetworkStack};
+use crate::secret::Secret;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Something that can be picked from a list.
+pub trait Choice {
+    fn label(&self) -> String;
+
+    /// Shown after the label, aligned across the list.
+    fn description(&self) -> Option<String> {
+        None
+    }
+
+    /// How the whole line is drawn, e.g. red for an answer with a problem.
+    fn style(&self) -> Style {
+        Style::default()
+    }
+}
+
+impl Choice for &'static str {
+    fn label(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Choice for String {
+    fn label(&self) -> String {
+        self.clone()
+    }
+}
+
+impl Choice for KernelSelection {
+    fn label(&self) -> String {
+        KernelSelection::label(self).to_string()
+    }
+}
+
+impl Choice for GpuDriver {
+    fn label(&self) -> String {
+        GpuDriver::label(self).to_string()
+    }
+}
+
+impl Choice for DesktopEnv {
+    fn label(&self) -> String {
+        self.def().label.to_string()
+    }
+
+    fn description(&self) -> Option<String> {
+        Some(self.def().description.to_string())
+    }
+}
+
+impl Choice for DisplayManager {
+    fn label(&self) -> String {
+        DisplayManager::label(self).to_string()
+    }
+}
+
+impl Choice for AurHelper {
+    fn label(&self) -> String {
+        AurHelper::label(self).to_string()
+    }
+}
+
+impl Choice for NetworkStack {
+    fn label(&self) -> String {
+        NetworkStack::label(self).to_string()
+    }
+}
+
+/// What a list did with a key.
+pub enum ListEvent<T> {
+    /// Not a list key; the page may handle it.
+    Ignored,
+    Moved,
+    /// Enter on an item.
+    Chosen(T),
+    /// Space on an item of a checkbox list.
+    Toggled(T),
+    /// Esc or Backspace.
+    Cancelled,
+}
+
+fn list_items<T: Choice>(items: &[T], mark: impl Fn(&T) -> &'static str) -> Vec<ListItem<'static>> {
+    let width = items.iter().map(|i| i.label().chars().count()).max().unwrap_or(0);
+    items
+        .iter()
+        .map(|item| {
+            let text = match item.description() {
+                Some(desc) => format!("{}{:<width$} {}", mark(item), item.label(), desc),
+                None => format!("{}{}", mark(item), item.label()),
+            };
+            ListItem::new(text).style(item.style())
+        })
+        .collect()
+}
+
+fn render_list(f: &mut Frame, area: Rect, title: &str, items: Vec<ListItem>, selected: usize) {
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    let list = List::new(items)
+        .block(Block::default().title(title.to_string()).borders(Borders::ALL))
+        .highlight_style(Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Cursor movement shared by the lists; wraps around at both ends.
+fn move_cursor(selected: &mut usize, len: usize, code: KeyCode) -> bool {
+    if len == 0 {
+        return false;
+    }
+    match code {
+        KeyCode::Up => *selected = (*selected + len - 1) % len,
+        KeyCode::Down => *selected = (*selected + 1) % len,
+        KeyCode::Home => *selected = 0,
+        KeyCode::End => *selected = len - 1,
+        _ => return false,
+    }
+    true
+}
+
+/// A single-choice list over typed items.
+pub struct SelectList<T> {
+    items: Vec<T>,
+    selected: usize,
+}
+
+impl<T> Default for SelectList<T> {
+    fn default() -> Self {
+        SelectList { items: Vec::new(), selected: 0 }
+    }
+}
+
+impl<T: Choice + Clone> SelectList<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        SelectList { items, selected: 0 }
+    }
+
+    /// Replaces the items, keeping the cursor in range.
+    pub fn set_items(&mut self, items: Vec<T>) {
+        self.items = items;
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        self.items.get(self.selected)
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> ListEvent<T> {
+        if move_cursor(&mut self.selected, self.items.len(), code) {
+            return ListEvent::Moved;
+        }
+        match code {
+            KeyCode::Enter => match self.selected() {
+                Some(item) => ListEvent::Chosen(item.clone()),
+                None => ListEvent::Ignored,
+            },
+            KeyCode::Esc | KeyCode::Backspace => ListEvent::Cancelled,
+            _ => ListEvent::Ignored,
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, title: &str) {
+        render_list(f, area, title, list_items(&self.items, |_| ""), self.selected);
+    }
+}
+
+impl<T: PartialEq> SelectList<T> {
+    /// Moves the cursor to `value`, if it is in the list.
+    pub fn select(&mut self, value: &T) {
+        if let Some(pos) = self.items.iter().position(|i| i == value) {
+            self.selected = pos;
+        }
+    }
+}
+
+/// A multi-select list. Whether an item is checked comes from the answers,
+/// so the list only tracks the cursor.
+pub struct CheckboxList<T> {
+    items: Vec<T>,
+    selected: usize,
+}
+
+impl<T> Default for CheckboxList<T> {
+    fn default() -> Self {
+        CheckboxList { items: Vec::new(), selected: 0 }
+    }
+}
+
+impl<T: Choice + Clone> CheckboxList<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        CheckboxList { items, selected: 0 }
+    }
+
+    pub fn set_items(&mut self, items: Vec<T>) {
+        self.items = items;
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        self.items.get(self.selected)
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> ListEvent<T> {
+        if move_cursor(&mut self.selected, self.items.len(), code) {
+            return ListEvent::Moved;
+        }
+        match (code, self.selected()) {
+            (KeyCode::Char(' '), Some(item)) => ListEvent::Toggled(item.clone()),
+            (KeyCode::Enter, Some(item)) => ListEvent::Chosen(item.clone()),
+            (KeyCode::Esc | KeyCode::Backspace, _) => ListEvent::Cancelled,
+            _ => ListEvent::Ignored,
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, title: &str, checked: impl Fn(&T) -> bool) {
+        let items = list_items(&self.items, |item| if checked(item) { "[x] " } else { "[ ] " });
+        render_list(f, area, title, items, self.selected);
+    }
+}
+
+/// Single-line text entry with a cursor.
+///
+/// Left/Right, Home/End, Backspace/Delete, Ctrl+W (or Alt/Ctrl+Backspace)
+/// to delete the previous word, and pasted text. The text is kept in a
+/// [`Secret`] so the same editing backs [`PasswordField`] without copies.
+#[derive(Default)]
+pub struct TextInput {
+    value: Secret,
+    /// Byte offset into `value`, always on a char boundary.
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn value(&self) -> &str {
+        self.value.expose()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Replaces the text and puts the cursor at the end.
+    pub fn set(&mut self, value: &str) {
+        self.value.clear();
+        self.value.push_str(value);
+        self.cursor = self.value.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    /// Moves the text out, leaving the input empty.
+    pub fn take(&mut self) -> String {
+        let value = self.value().to_string();
+        self.clear();
+        value
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.value()[..self.cursor].char_indices().next_back().map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.value()[self.cursor..].chars().next().map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+
+    fn delete_word(&mut self) {
+        let before = &self.value()[..self.cursor];
+        let end = before.trim_end().len();
+        let start = before[..end]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        self.value.remove_range(start, self.cursor);
+        self.cursor = start;
+    }
+
+    fn insert(&mut self, c: char) {
+        self.value.insert_str(self.cursor, c.encode_utf8(&mut [0; 4]));
+        self.cursor += c.len_utf8();
+    }
+
+    /// Inserts pasted text; line breaks and other control characters are dropped.
+    pub fn paste(&mut self, text: &str) {
+        for c in text.chars().filter(|c| !c.is_control()) {
+            self.insert(c);
+        }
+    }
+
+    /// Edits the text; false for keys the input does not use, including
+    /// Delete at the end of the text.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('w') if ctrl => self.delete_word(),
+            KeyCode::Backspace if ctrl || alt => self.delete_word(),
+            KeyCode::Char(c) if !ctrl && !alt => self.insert(c),
+            KeyCode::Backspace if self.cursor > 0 => {
+                let start = self.prev_boundary();
+                self.value.remove_range(start, self.cursor);
+                self.cursor = start;
+            }
+            KeyCode::Delete if self.cursor < self.value.len() => {
+                let end = self.next_boundary();
+                self.value.remove_range(self.cursor, end);
+            }
+            KeyCode::Left => self.cursor = self.prev_boundary(),
+            KeyCode::Right => self.cursor = self.next_boundary(),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn render_text(&self, f: &mut Frame, area: Rect, title: &str, focused: bool, mask: bool) {
+        let chars: Vec<char> = if mask { vec!['*'; self.value().chars().count()] } else { self.value().chars().collect() };
+        let cursor = self.value()[..self.cursor].chars().count();
+        // Scroll horizontally so the cursor stays visible.
+        let width = area.width.saturating_sub(2) as usize;
+        let offset = (cursor + 1).saturating_sub(width);
+        let visible: String = chars.iter().skip(offset).take(width).collect();
+        let title_style = if focused { Style::default().fg(Color::Yellow) } else { Style::default() };
+        let block = Block::default().borders(Borders::ALL).title(Span::styled(title.to_string(), title_style));
+        f.render_widget(Paragraph::new(visible).block(block), area);
+        // Too narrow to show any text, so there is nowhere to put the cursor.
+        if focused && width > 0 {
+            f.set_cursor(area.x + 1 + (cursor - offset) as u16, area.y + 1);
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, title: &str, focused: bool) {
+        self.render_text(f, area, title, focused, false);
+    }
+}
+
+/// Room reserved up front for a password, so typing one never reallocates
+/// (and leaves copies of) the buffer.
+const PASSWORD_CAPACITY: usize = 256;
+
+/// A text input that shows `*` for every character and hands its text
+/// over as a [`Secret`].
+pub struct PasswordField {
+    input: TextInput,
+}
+
+impl Default for PasswordField {
+    fn default() -> Self {
+        PasswordField { input: TextInput { value: Secret::with_capacity(PASSWORD_CAPACITY), cursor: 0 } }
+    }
+}
+
+impl PasswordField {
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    pub fn matches(&self, other: &PasswordField) -> bool {
+        self.input.value() == other.input.value()
+    }
+
+    /// Hands the buffer over as is and starts a fresh one.
+    pub fn take(&mut self) -> Secret {
+        self.input.cursor = 0;
+        std::mem::replace(&mut self.input.value, Secret::with_capacity(PASSWORD_CAPACITY))
+    }
+
+    pub fn clear(&mut self) {
+        self.input.clear();
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.input.paste(text);
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.input.handle_key(key)
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, title: &str, focused: bool) {
+        self.input.render_text(f, area, title, focused, true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn renders_text_input_in_any_width() {
+        let mut input = TextInput::default();
+        input.paste("hostname");
+        for width in 0..12 {
+            let mut terminal = Terminal::new(TestBackend::new(width, 3)).unwrap();
+            terminal.draw(|f| input.render(f, f.size(), "Hostname", true)).unwrap();
+        }
+    }
+
+    fn typed(input: &mut TextInput, codes: &[KeyCode]) {
+        for code in codes {
+            input.handle_key(KeyEvent::new(*code, KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn moves_the_cursor_by_characters() {
+        let mut input = TextInput::default();
+        input.paste("héllo");
+        typed(&mut input, &[KeyCode::Left, KeyCode::Left, KeyCode::Left, KeyCode::Left, KeyCode::Backspace]);
+        assert_eq!(input.value(), "éllo");
+        typed(&mut input, &[KeyCode::Delete, KeyCode::Char('e')]);
+        assert_eq!(input.value(), "ello");
+        typed(&mut input, &[KeyCode::End, KeyCode::Char('!'), KeyCode::Home, KeyCode::Char('>')]);
+        assert_eq!(input.value(), ">ello!");
+        // Nothing to delete at the end, so the key is left to the page.
+        typed(&mut input, &[KeyCode::End]);
+        assert!(!input.handle_key(KeyEvent::new(KeyCode::Delete, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn deletes_the_word_before_the_cursor() {
+        let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        let mut input = TextInput::default();
+        input.paste("mount /dev/sda1  ");
+        input.handle_key(ctrl_w);
+        assert_eq!(input.value(), "mount ");
+        input.handle_key(ctrl_w);
+        assert_eq!(input.value(), "");
+
+        // A no-break space is whitespace too, and two bytes long.
+        input.paste("grüße\u{a0}welt");
+        input.handle_key(ctrl_w);
+        assert_eq!(input.value(), "grüße\u{a0}");
+        input.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::ALT));
+        assert_eq!(input.value(), "");
+    }
+
+    #[test]
+    fn pastes_at_the_cursor_without_control_characters() {
+        let mut input = TextInput::default();
+        input.set("ab");
+        typed(&mut input, &[KeyCode::Left]);
+        input.paste("x\ny\t");
+        assert_eq!(input.value(), "axyb");
+        typed(&mut input, &[KeyCode::Char('z')]);
+        assert_eq!(input.value(), "axyzb");
+    }
+}
@@ -0,0 +1,273 @@
+use crate::mirrors::{CurlClient, HttpClient};
+use crate::network::{self, IWD_DIR};
+use crate::secret::Secret;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// Plain-HTTP page Arch serves for connectivity checks.
+const CHECK_URL: &str = "http://ping.archlinux.org/nm-check.txt";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// DHCP usually completes a few seconds after association.
+const CHECK_ATTEMPTS: u32 = 10;
+/// iwd scans asynchronously; results are read after this delay.
+const SCAN_WAIT: Duration = Duration::from_secs(3);
+
+/// A network seen by a scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessPoint {
+    pub ssid: String,
+    /// `open`, `psk` or `8021x`, as reported by iwd.
+    pub security: String,
+    pub signal_dbm: i32,
+    pub connected: bool,
+}
+
+impl AccessPoint {
+    pub fn is_open(&self) -> bool {
+        self.security == "open"
+    }
+
+    /// WPA personal, joined with a passphrase.
+    pub fn is_psk(&self) -> bool {
+        self.security == "psk"
+    }
+
+    /// 802.1X needs certificates and an EAP method, which the installer
+    /// does not ask for.
+    pub fn is_supported(&self) -> bool {
+        self.is_open() || self.is_psk()
+    }
+
+    /// Signal strength as 1-4 bars.
+    pub fn bars(&self) -> usize {
+        match self.signal_dbm {
+            s if s >= -55 => 4,
+            s if s >= -67 => 3,
+            s if s >= -75 => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Scanning and joining networks; swap in another backend to change tools.
+/// Backends are used from the page's background threads.
+pub trait WifiBackend: Send + Sync {
+    fn scan(&self, interface: &str) -> Result<Vec<AccessPoint>, String>;
+    fn connect(&self, interface: &str, ap: &AccessPoint, passphrase: Option<&str>) -> Result<(), String>;
+
+    /// Waits until the internet is reachable.
+    fn wait_online(&self) -> Result<(), String> {
+        wait_for_connectivity(&CurlClient)
+    }
+}
+
+/// The live ISO runs iwd, so the default backend drives it through `iwctl`.
+pub struct Iwctl;
+
+fn iwctl(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("iwctl").args(args).output().map_err(|e| format!("iwctl: {}", e))?;
+    let stdout = strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        let stderr = strip_ansi(&String::from_utf8_lossy(&output.stderr));
+        Err(format!("`iwctl {}` failed: {}", args.join(" "), if stderr.trim().is_empty() { stdout } else { stderr }.trim()))
+    }
+}
+
+impl WifiBackend for Iwctl {
+    fn scan(&self, interface: &str) -> Result<Vec<AccessPoint>, String> {
+        iwctl(&["station", interface, "scan"])?;
+        thread::sleep(SCAN_WAIT);
+        iwctl(&["station", interface, "get-networks", "rssi-dbms"]).map(|out| parse_networks(&out))
+    }
+
+    /// The passphrase never goes on the command line, where any process
+    /// could read it: it is written to the iwd profile that `connect` uses.
+    /// Only PSK networks get a profile; 802.1X ones are refused.
+    fn connect(&self, interface: &str, ap: &AccessPoint, passphrase: Option<&str>) -> Result<(), String> {
+        if !ap.is_supported() {
+            return Err(format!("{} uses {} security, which is not supported; connect with iwctl first", ap.ssid, ap.security));
+        }
+        let ssid = ap.ssid.as_str();
+        let previous = match passphrase {
+            Some(passphrase) if ap.is_psk() => Some(write_profile(ssid, passphrase)?),
+            _ => None,
+        };
+        let result = iwctl(&["station", interface, "connect", ssid]).map(|_| ());
+        if let (Err(_), Some(previous)) = (&result, previous) {
+            // Do not leave a profile with a wrong passphrase behind.
+            let path = profile_path(ssid);
+            let _ = match previous {
+                Some(contents) => fs::write(&path, contents.expose()),
+                None => fs::remove_file(&path),
+            };
+        }
+        result
+    }
+}
+
+fn profile_path(ssid: &str) -> String {
+    format!("{}/{}.psk", IWD_DIR, network::encode_ssid(ssid))
+}
+
+/// Writes a 0600 iwd profile holding `passphrase` and returns the profile it
+/// replaced, if any.
+fn write_profile(ssid: &str, passphrase: &str) -> Result<Option<Secret>, String> {
+    let path = profile_path(ssid);
+    let previous = fs::read_to_string(&path).ok().map(Secret::new);
+    fs::create_dir_all(IWD_DIR).map_err(|e| format!("{}: {}", IWD_DIR, e))?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&path)
+        .map_err(|e| format!("{}: {}", path, e))?;
+    let mut contents = Secret::default();
+    contents.push_str("[Security]\nPassphrase=");
+    contents.push_str(passphrase);
+    contents.push_str("\n");
+    file.write_all(contents.expose().as_bytes()).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(previous)
+}
+
+/// iwctl colors its tables even when not attached to a terminal.
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parses the `get-networks rssi-dbms` table: a title, then the column
+/// headers between two dashed rules, then one row per network with the
+/// connected one marked by `>`.
+pub fn parse_networks(output: &str) -> Vec<AccessPoint> {
+    let mut rules = 0;
+    let mut networks = Vec::new();
+    for line in output.lines() {
+        if line.trim_start().starts_with("---") {
+            rules += 1;
+            continue;
+        }
+        if rules < 2 {
+            continue;
+        }
+        let line = line.trim();
+        let (connected, line) = match line.strip_prefix('>') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, line),
+        };
+        let Some((rest, signal)) = line.rsplit_once(char::is_whitespace) else {
+            continue;
+        };
+        let Some((ssid, security)) = rest.trim_end().rsplit_once(char::is_whitespace) else {
+            continue;
+        };
+        let Ok(signal) = signal.parse::<i32>() else {
+            continue;
+        };
+        networks.push(AccessPoint {
+            ssid: ssid.trim().to_string(),
+            security: security.to_string(),
+            // iwd reports hundredths of a dBm.
+            signal_dbm: if signal.abs() > 200 { signal / 100 } else { signal },
+            connected,
+        });
+    }
+    networks.sort_by_key(|ap| std::cmp::Reverse(ap.signal_dbm));
+    networks
+}
+
+/// Retries until the check URL answers, giving DHCP time to finish.
+pub fn wait_for_connectivity(client: &dyn HttpClient) -> Result<(), String> {
+    let mut last_error = String::new();
+    for attempt in 0..CHECK_ATTEMPTS {
+        if attempt > 0 {
+            thread::sleep(Duration::from_secs(1));
+        }
+        match client.download(CHECK_URL, CHECK_TIMEOUT) {
+            Ok(_) => return Ok(()),
+            Err(err) => last_error = err,
+        }
+    }
+    Err(format!("{} is unreachable: {}", CHECK_URL, last_error))
+}
+
+/// Results sent back from the background scan and connect threads.
+pub enum WifiUpdate {
+    Scanned(Result<Vec<AccessPoint>, String>),
+    Online(Result<(), String>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETWORKS: &str = "\
+                               Available networks
+--------------------------------------------------------------------------------
+      Network name                      Security            Signal
+--------------------------------------------------------------------------------
+      Cafe Free                         open                -7200
+  >   Home                              psk                 -4500
+      Office                            8021x               -60
+";
+
+    #[test]
+    fn parses_networks_strongest_first() {
+        let networks = parse_networks(NETWORKS);
+        assert_eq!(
+            networks,
+            vec![
+                AccessPoint { ssid: "Home".into(), security: "psk".into(), signal_dbm: -45, connected: true },
+                AccessPoint { ssid: "Office".into(), security: "8021x".into(), signal_dbm: -60, connected: false },
+                AccessPoint { ssid: "Cafe Free".into(), security: "open".into(), signal_dbm: -72, connected: false },
+            ]
+        );
+        assert!(networks[2].is_open());
+        assert_eq!(networks[0].bars(), 4);
+    }
+
+    #[test]
+    fn refuses_8021x_before_writing_a_profile() {
+        let office = AccessPoint { ssid: "Office".into(), security: "8021x".into(), signal_dbm: -60, connected: false };
+        assert!(!office.is_supported());
+        let err = Iwctl.connect("wlan0", &office, Some("hunter22")).unwrap_err();
+        assert!(err.contains("not supported"), "{}", err);
+        assert!(!std::path::Path::new(&profile_path("Office")).exists());
+    }
+
+    #[test]
+    fn parses_empty_scan() {
+        assert!(parse_networks("").is_empty());
+        assert!(parse_networks("No networks available\n").is_empty());
+    }
+
+    #[test]
+    fn strips_color_codes() {
+        assert_eq!(strip_ansi("\x1b[1;90m  >   \x1b[0mHome\x1b[0m"), "  >   Home");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn parses_colored_table() {
+        let colored = NETWORKS.replace(">", "\x1b[1;90m>\x1b[0m");
+        assert_eq!(parse_networks(&strip_ansi(&colored)).len(), 3);
+    }
+}